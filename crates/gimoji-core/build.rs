@@ -1,4 +1,6 @@
 use std::{
+    borrow::Cow,
+    collections::BTreeMap,
     env::var_os,
     error::Error,
     fs::{read_to_string, File},
@@ -12,11 +14,30 @@ use serde::Deserialize;
 #[derive(Deserialize, Debug, Bake)]
 #[databake(path = gimoji_core::emoji)]
 pub struct Emoji<'e> {
-    pub code: &'e str,
-    pub description: &'e str,
-    pub emoji: &'e str,
-    pub entity: &'e str,
-    pub name: &'e str,
+    #[serde(borrow)]
+    pub code: Cow<'e, str>,
+    #[serde(borrow)]
+    pub description: Cow<'e, str>,
+    #[serde(borrow)]
+    pub emoji: Cow<'e, str>,
+    #[serde(borrow)]
+    pub entity: Cow<'e, str>,
+    #[serde(borrow)]
+    pub name: Cow<'e, str>,
+    pub semver: Option<SemverBump>,
+    #[serde(borrow, default, rename = "type")]
+    pub commit_type: Option<Cow<'e, str>>,
+    #[serde(borrow, default)]
+    pub category: Option<Cow<'e, str>>,
+}
+
+#[derive(Deserialize, Debug, Bake)]
+#[databake(path = gimoji_core::emoji)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverBump {
+    Patch,
+    Minor,
+    Major,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -47,14 +68,82 @@ fn main() -> Result<(), Box<dyn Error>> {
     let baked = (&emojis.gitmojis[..]).bake(&Default::default()).to_string();
 
     let out = format!("pub const EMOJIS: &[crate::emoji::Emoji] = {baked};\n");
+    write_out("emojis.rs", &out)?;
+
+    let trie = prefix_trie(&emojis.gitmojis);
+    let out = format!("const NODES: &[Node] = &[{trie}];\n");
+    write_out("prefix_trie.rs", &out)?;
 
+    Ok(())
+}
+
+fn write_out(file_name: &str, contents: &str) -> Result<(), Box<dyn Error>> {
     let out_dir = var_os("OUT_DIR").unwrap();
-    let dest_path = PathBuf::from(out_dir).join("emojis.rs");
+    let dest_path = PathBuf::from(out_dir).join(file_name);
     let mut dest_file = File::create(dest_path)?;
-    dest_file.write_all(out.as_bytes())?;
+    dest_file.write_all(contents.as_bytes())?;
 
     Ok(())
 }
 
+/// Build the byte trie `gimoji_core::prefix` walks to find the emoji a text
+/// starts with, keyed on each emoji's glyph and its `:code:`, and render its
+/// nodes as Rust expressions. Node 0 is the root.
+///
+/// Glyphs are keyed with their variation selectors stripped; the lookup
+/// skips them in the text instead, so `⚡` and `⚡️` find the same emoji.
+fn prefix_trie(emojis: &[Emoji<'_>]) -> String {
+    #[derive(Default)]
+    struct TrieNode {
+        edges: BTreeMap<u8, usize>,
+        emoji: Option<usize>,
+    }
+
+    let mut nodes = vec![TrieNode::default()];
+    for (index, emoji) in emojis.iter().enumerate() {
+        let glyph: String = emoji
+            .emoji
+            .chars()
+            .filter(|c| !VARIATION_SELECTORS.contains(c))
+            .collect();
+        for key in [glyph.as_str(), &emoji.code] {
+            let mut node = 0;
+            for byte in key.bytes() {
+                node = match nodes[node].edges.get(&byte) {
+                    Some(&next) => next,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let next = nodes.len() - 1;
+                        nodes[node].edges.insert(byte, next);
+                        next
+                    }
+                };
+            }
+            // First one wins, should two emojis ever share a key.
+            nodes[node].emoji.get_or_insert(index);
+        }
+    }
+    assert!(nodes.len() <= u16::MAX as usize, "prefix trie outgrew u16");
+
+    nodes
+        .iter()
+        .map(|node| {
+            let edges: Vec<String> = node
+                .edges
+                .iter()
+                .map(|(byte, next)| format!("({byte}, {next})"))
+                .collect();
+            format!(
+                "Node {{ edges: &[{}], emoji: {:?} }}",
+                edges.join(", "),
+                node.emoji
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 const EMOJI_FILE: &str = "emojis.json";
 const ROOT_EMOJI_FILE: &str = "../../emojis.json";
+/// Text (U+FE0E) and emoji (U+FE0F) presentation selectors.
+const VARIATION_SELECTORS: [char; 2] = ['\u{fe0e}', '\u{fe0f}'];
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{borrow::Cow, time::Duration};
 
 use ratatui::{
     layout::{Position, Rect},
@@ -8,6 +8,9 @@ use ratatui::{
 use crate::{
     colors::Colors,
     emoji::Emoji,
+    history::{self, HistoryEntry},
+    keywords,
+    preview::Preview,
     search_entry::SearchEntry,
     selection_view::{EmojiSource, SelectionView, EMOJI_COLUMN_WIDTH, HIGHLIGHT_GUTTER_WIDTH},
     toast::Toast,
@@ -35,24 +38,54 @@ pub enum Action {
     Scroll(i32),
     PickFocused,
     PickAt(usize),
+    /// Toggle the selected emoji for picking along with others. Once any
+    /// are, [`Action::PickFocused`] picks those instead.
+    Toggle,
     Cancel,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Outcome {
     Continue,
-    Picked(String),
+    Picked(Emoji),
+    /// Several emojis picked together with [`Action::Toggle`], in the order
+    /// they were toggled.
+    PickedMany(Vec<Emoji>),
+    /// The emojis picked along with the details the user was asked for
+    /// afterwards; see [`App::set_prompts`].
+    Described(Vec<Emoji>, Details),
     Cancelled,
 }
 
+/// What the user typed in after picking an emoji, for the rest of the
+/// commit subject.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Details {
+    /// The scope, e.g. `parser`, if they gave one.
+    pub scope: Option<String>,
+    /// The summary of the change; empty if they left it to the editor.
+    pub summary: String,
+}
+
 pub struct App<'c> {
     search: SearchEntry<'c>,
     selection: SelectionView<'c>,
     colors: &'c Colors,
     toast: Option<Toast>,
     emoji_source: EmojiSource,
-    last_rendered_rows: Vec<Rect>,
-    last_row_offset: usize,
+    history: Vec<HistoryEntry>,
+    /// The fields to ask for after a pick.
+    prompts: Vec<Field>,
+    /// While asking for them, what's been picked and typed in so far.
+    prompting: Option<Prompting<'c>>,
+    /// The commit subject written so far, shown for context.
+    subject: Option<String>,
+    /// Glyphs suggested from outside, listed after those `subject` hints at.
+    suggested: Vec<String>,
+    /// Shown below the list, if set.
+    preview: Option<Preview>,
+    /// Each list row of the last render, with the index of its emoji.
+    last_rendered_rows: Vec<(Rect, usize)>,
     last_visible_emojis: Vec<VisibleEmoji>,
     last_emoji_band: Option<Rect>,
     last_search_area: Option<Rect>,
@@ -63,18 +96,23 @@ pub struct App<'c> {
 /// Overlay-rendering frontends consume this list to paint the emoji glyphs
 /// at the right cell coordinates; frontends that emit glyphs into the
 /// buffer ignore it.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct VisibleEmoji {
     /// Cell rect of the emoji glyph column (3 cells wide × 1 cell high).
     pub cell: Rect,
     /// Unicode emoji to render at that position.
-    pub emoji: &'static str,
+    pub emoji: Cow<'static, str>,
 }
 
 impl<'c> App<'c> {
     /// Build a picker that emits emoji glyphs directly into the buffer.
     /// The host (e.g. crossterm + terminal) draws them from its own font.
-    pub fn new(emojis: &'static [Emoji], colors: &'c Colors) -> Self {
+    ///
+    /// `emojis` is usually [`EMOJIS`], but can as well be a list built at
+    /// runtime, e.g. with a project's own emojis added from its config.
+    ///
+    /// [`EMOJIS`]: crate::emoji::EMOJIS
+    pub fn new(emojis: impl Into<Cow<'static, [Emoji]>>, colors: &'c Colors) -> Self {
         Self::build(emojis, colors, EmojiSource::InCanvas)
     }
 
@@ -83,19 +121,31 @@ impl<'c> App<'c> {
     /// glyphs. Keeps column alignment stable across rows even when the
     /// emoji is a ZWJ sequence whose `unicode-width` count doesn't match
     /// its rendered width.
-    pub fn with_emoji_overlay(emojis: &'static [Emoji], colors: &'c Colors) -> Self {
+    pub fn with_emoji_overlay(
+        emojis: impl Into<Cow<'static, [Emoji]>>,
+        colors: &'c Colors,
+    ) -> Self {
         Self::build(emojis, colors, EmojiSource::Overlay)
     }
 
-    fn build(emojis: &'static [Emoji], colors: &'c Colors, source: EmojiSource) -> Self {
+    fn build(
+        emojis: impl Into<Cow<'static, [Emoji]>>,
+        colors: &'c Colors,
+        source: EmojiSource,
+    ) -> Self {
         Self {
             search: SearchEntry::new(colors),
-            selection: SelectionView::new(emojis, colors, source),
+            selection: SelectionView::new(emojis.into(), colors, source),
             colors,
             toast: None,
             emoji_source: source,
+            history: Vec::new(),
+            prompts: Vec::new(),
+            prompting: None,
+            subject: None,
+            suggested: Vec::new(),
+            preview: None,
             last_rendered_rows: Vec::new(),
-            last_row_offset: 0,
             last_visible_emojis: Vec::new(),
             last_emoji_band: None,
             last_search_area: None,
@@ -107,6 +157,21 @@ impl<'c> App<'c> {
         self.search.text()
     }
 
+    /// Text of the field being typed into: the search, or after a pick, the
+    /// detail being asked for.
+    pub fn input_text(&self) -> &str {
+        match &self.prompting {
+            Some(prompting) => prompting.fields[prompting.current].1.text(),
+            None => self.search.text(),
+        }
+    }
+
+    /// Whether the details are being asked for after a pick, so printable
+    /// keys type into them rather than act on the list.
+    pub fn is_prompting(&self) -> bool {
+        self.prompting.is_some()
+    }
+
     /// Swap the palette in place, e.g. when the OS colour scheme flips.
     ///
     /// Everything the user built up — search text, selection, scroll
@@ -120,7 +185,127 @@ impl<'c> App<'c> {
         }
     }
 
+    /// Show or hide each emoji's semver bump as an extra column in the
+    /// list. Hidden by default.
+    pub fn set_show_semver(&mut self, show: bool) {
+        self.selection.set_show_semver(show);
+    }
+
+    /// While the search is empty, list the emojis under a header for each
+    /// category, e.g. "Fixes" or "Deps", after any suggested or recent ones.
+    /// Off by default.
+    pub fn set_grouped(&mut self, grouped: bool) {
+        self.selection.set_grouped(grouped);
+    }
+
+    /// After a pick, ask for a scope and/or a one-line summary before
+    /// handing back [`Outcome::Described`] rather than [`Outcome::Picked`].
+    ///
+    /// Enter moves on to the next field, Escape back to the previous one or
+    /// to the list. Both fields can be left empty. Off by default.
+    pub fn set_prompts(&mut self, scope: bool, summary: bool) {
+        self.prompts = [(Field::Scope, scope), (Field::Summary, summary)]
+            .into_iter()
+            .filter_map(|(field, ask)| ask.then_some(field))
+            .collect();
+    }
+
+    /// Load the history of earlier picks, e.g. from a [`History`]. While
+    /// the search is empty, the most used emojis are listed first.
+    ///
+    /// Picks made from here on are added to the history, but don't reorder
+    /// the list until it's set again: rows shouldn't shuffle under the
+    /// pointer mid-session.
+    ///
+    /// [`History`]: crate::history::History
+    pub fn set_history(&mut self, entries: Vec<HistoryEntry>) {
+        let recent = history::most_used(&entries, RECENT_LIMIT)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        self.selection.set_recent(recent);
+        self.history = entries;
+    }
+
+    /// Emoji glyphs to suggest, e.g. from what a commit changes: they're
+    /// listed first, ahead of the history's, while the search is empty, so
+    /// the first of them starts out selected.
+    pub fn set_suggested(&mut self, glyphs: Vec<String>) {
+        self.suggested = glyphs;
+        self.update_suggested();
+    }
+
+    /// Show the commit `subject` written so far (e.g. with `git commit -m`)
+    /// above the search, and suggest the emojis its words hint at ahead of
+    /// those of [`Self::set_suggested`]; see [`keywords::hinted`].
+    pub fn set_subject(&mut self, subject: impl Into<String>) {
+        let subject = subject.into();
+        self.subject = (!subject.trim().is_empty()).then_some(subject);
+        self.update_suggested();
+    }
+
+    /// Show `preview` below the list, with the selected emoji (or the
+    /// toggled ones) in front of its subject. It takes the place of the
+    /// subject above the search. Off by default.
+    pub fn set_preview(&mut self, preview: Option<Preview>) {
+        self.preview = preview;
+    }
+
+    fn update_suggested(&mut self) {
+        let hinted = self.subject.as_deref().map(keywords::hinted);
+        let mut glyphs: Vec<String> = hinted
+            .iter()
+            .flatten()
+            .map(|emoji| emoji.emoji().to_string())
+            .collect();
+        for glyph in &self.suggested {
+            if !glyphs.contains(glyph) {
+                glyphs.push(glyph.clone());
+            }
+        }
+        self.selection.set_suggested(glyphs);
+        let mut view = self.selection.filtered_view(self.search.text());
+        view.select(0);
+    }
+
+    /// Move the selection onto the emoji with `code` (e.g. `:bug:`), say to
+    /// start from the one a commit message already has. Returns whether
+    /// the current list has it.
+    pub fn select(&mut self, code: &str) -> bool {
+        let mut view = self.selection.filtered_view(self.search.text());
+        let index = (0..view.visible_count())
+            .find(|&i| view.get(i).is_some_and(|emoji| emoji.code() == code));
+        if let Some(index) = index {
+            view.select(index);
+        }
+
+        index.is_some()
+    }
+
+    /// The history of picks, including this session's, for saving.
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
     pub fn handle(&mut self, action: Action) -> Outcome {
+        let outcome = match self.prompting {
+            Some(_) => self.interpret_prompt(action),
+            None => self.interpret(action),
+        };
+        let picked = match &outcome {
+            Outcome::Picked(emoji) => std::slice::from_ref(emoji),
+            Outcome::PickedMany(emojis) | Outcome::Described(emojis, _) => emojis,
+            Outcome::Continue | Outcome::Cancelled => return outcome,
+        };
+        for emoji in picked {
+            history::record(&mut self.history, emoji.emoji());
+        }
+        self.selection.clear_toggled();
+
+        outcome
+    }
+
+    fn interpret(&mut self, action: Action) -> Outcome {
         match action {
             Action::Append(c) => {
                 self.search.append(c);
@@ -159,30 +344,106 @@ impl<'c> App<'c> {
                 }
                 Outcome::Continue
             }
+            Action::PickFocused if !self.selection.toggled().is_empty() => {
+                let emojis = self.selection.toggled().to_vec();
+                self.pick(emojis)
+            }
             Action::PickFocused => {
                 let view = self.selection.filtered_view(self.search.text());
-                match view.selected() {
-                    Some(emoji) => Outcome::Picked(emoji.emoji().to_string()),
+                match view.selected().cloned() {
+                    Some(emoji) => self.pick(vec![emoji]),
                     None => Outcome::Continue,
                 }
             }
             Action::PickAt(i) => {
                 let mut view = self.selection.filtered_view(self.search.text());
-                match view.get(i) {
+                match view.get(i).cloned() {
                     Some(emoji) => {
                         // Leave the selection on the row that was picked, so
                         // the highlight follows the pointer rather than
                         // staying wherever the keyboard last left it.
                         view.select(i);
-                        Outcome::Picked(emoji.emoji().to_string())
+                        self.pick(vec![emoji])
                     }
                     None => Outcome::Continue,
                 }
             }
+            Action::Toggle => {
+                let mut view = self.selection.filtered_view(self.search.text());
+                view.toggle_selected();
+                Outcome::Continue
+            }
             Action::Cancel => Outcome::Cancelled,
         }
     }
 
+    /// Hand `emojis` back, or first ask for the details if there are any to
+    /// ask for.
+    fn pick(&mut self, mut emojis: Vec<Emoji>) -> Outcome {
+        if self.prompts.is_empty() {
+            return match emojis.len() {
+                1 => Outcome::Picked(emojis.remove(0)),
+                _ => Outcome::PickedMany(emojis),
+            };
+        }
+
+        let fields = self
+            .prompts
+            .iter()
+            .map(|&field| (field, field.entry(self.colors)))
+            .collect();
+        self.prompting = Some(Prompting {
+            emojis,
+            fields,
+            current: 0,
+        });
+
+        Outcome::Continue
+    }
+
+    fn interpret_prompt(&mut self, action: Action) -> Outcome {
+        let Some(Prompting {
+            fields, current, ..
+        }) = &mut self.prompting
+        else {
+            return Outcome::Continue;
+        };
+        let entry = &mut fields[*current].1;
+        match action {
+            Action::Append(c) => entry.append(c),
+            Action::Backspace => entry.delete_last(),
+            Action::SetSearch(text) => entry.set_text(text),
+            Action::ClearSearch => entry.delete_all(),
+            Action::PickFocused if *current + 1 < fields.len() => *current += 1,
+            Action::PickFocused => {
+                let Prompting { emojis, fields, .. } = self.prompting.take().expect("prompting");
+                let mut details = Details::default();
+                for (field, entry) in fields {
+                    let text = entry.text().trim();
+                    match field {
+                        Field::Scope => details.scope = Some(text.to_string()),
+                        Field::Summary => details.summary = text.to_string(),
+                    }
+                }
+                details.scope = details.scope.filter(|scope| !scope.is_empty());
+
+                return Outcome::Described(emojis, details);
+            }
+            // Back to the previous field, keeping what's typed in; from the
+            // first, back to the list.
+            Action::Cancel if *current > 0 => *current -= 1,
+            Action::Cancel => self.prompting = None,
+            // The list isn't showing.
+            Action::MoveUp
+            | Action::MoveDown
+            | Action::Scroll(_)
+            | Action::PickAt(_)
+            | Action::Toggle => {}
+        }
+
+        Outcome::Continue
+    }
+
     /// Show a confirmation toast for a freshly picked emoji. The prefix is
     /// rendered into the buffer; the emoji glyph goes into the buffer too,
     /// unless the picker was built for overlay rendering — see
@@ -220,10 +481,50 @@ impl<'c> App<'c> {
     /// generally want [`Self::render`].
     pub fn render_in_area(&mut self, frame: &mut Frame<'_>, area: Rect) {
         use ratatui::layout::{Constraint, Layout};
-        let chunks = Layout::default()
-            .constraints([Constraint::Min(5), Constraint::Percentage(100)].as_ref())
+        // A line above the search for the subject, if there's one to show,
+        // unless the preview below the list shows it.
+        let preview_height = match &self.prompting {
+            None => self.preview.as_ref().map(Preview::height),
+            Some(_) => None,
+        };
+        let subject = self
+            .subject
+            .as_deref()
+            .filter(|_| self.prompting.is_none() && preview_height.is_none());
+        let mut constraints = vec![Constraint::Min(5), Constraint::Percentage(100)];
+        if subject.is_some() {
+            constraints.insert(0, Constraint::Length(1));
+        }
+        if let Some(height) = preview_height {
+            constraints.push(Constraint::Length(height));
+        }
+        let mut chunks = Layout::default()
+            .constraints(constraints)
             .margin(1)
-            .split(area);
+            .split(area)
+            .to_vec();
+        if let Some(subject) = subject {
+            use ratatui::{
+                style::{Modifier, Style},
+                text::{Line, Span},
+                widgets::Paragraph,
+            };
+
+            let base = Style::default().fg(self.colors.unselected);
+            let line = Line::from(vec![
+                Span::styled(SUBJECT_LABEL, base.add_modifier(Modifier::DIM)),
+                Span::styled(subject, base),
+            ]);
+            frame.render_widget(Paragraph::new(line), chunks.remove(0));
+        }
+
+        if self.prompting.is_some() {
+            self.render_prompt(frame, chunks[0], chunks[1]);
+            if let Some(toast) = &mut self.toast {
+                toast.render(area, frame.buffer_mut());
+            }
+            return;
+        }
 
         frame.render_widget(&self.search, chunks[0]);
         self.last_search_area = Some(chunks[0]);
@@ -243,29 +544,38 @@ impl<'c> App<'c> {
         // view is dropped. Reading `view.offset()` *after* the render is
         // important: the Table widget updates the offset during render to
         // scroll the selection into view.
-        let mut visible: Vec<(u16, &'static str)> = Vec::new();
-        let row_offset;
-        {
+        let mut visible: Vec<(u16, usize, Cow<'static, str>)> = Vec::new();
+        let previewed = {
             let mut view = self.selection.filtered_view(self.search.text());
-            let visible_count = view.visible_count();
             frame.render_widget(&mut view, chunks[1]);
-            row_offset = view.offset();
-            let row_count = visible_count
+            let row_offset = view.offset();
+            let row_count = view
+                .row_count()
                 .saturating_sub(row_offset)
                 .min(visible_h as usize);
             visible.reserve_exact(row_count);
             for i in 0..row_count {
                 let y = inner_top + i as u16;
-                let emoji = match view.get(row_offset + i) {
-                    Some(e) => e.emoji(),
-                    None => continue,
+                // Section headers have no emoji to paint or pick.
+                let Some(index) = view.index_at_row(row_offset + i) else {
+                    continue;
                 };
-                visible.push((y, emoji));
+                let Some(emoji) = view.get(index) else {
+                    continue;
+                };
+                visible.push((y, index, emoji.glyph()));
+            }
+
+            match view.toggled() {
+                [] => view.selected().cloned().into_iter().collect(),
+                toggled => toggled.to_vec(),
             }
+        };
+        if let (Some(preview), Some(&area)) = (&self.preview, chunks.get(2)) {
+            preview.render(&previewed, self.colors, area, frame.buffer_mut());
         }
 
         self.last_rendered_rows.clear();
-        self.last_row_offset = row_offset;
         self.last_viewport_rows = visible_h as usize;
         self.last_visible_emojis.clear();
         // Extend the band by one cell into the block's top padding row so
@@ -281,13 +591,14 @@ impl<'c> App<'c> {
             width: EMOJI_COLUMN_WIDTH,
             height: band_h,
         });
-        for (y, emoji) in visible {
-            self.last_rendered_rows.push(Rect {
+        for (y, index, emoji) in visible {
+            let row = Rect {
                 x: inner_left,
                 y,
                 width: inner_w,
                 height: 1,
-            });
+            };
+            self.last_rendered_rows.push((row, index));
             self.last_visible_emojis.push(VisibleEmoji {
                 cell: Rect {
                     x: emoji_x,
@@ -304,19 +615,94 @@ impl<'c> App<'c> {
         }
     }
 
+    /// Render the field being typed into in place of the search, and what's
+    /// been picked and typed in so far in place of the list.
+    fn render_prompt(&mut self, frame: &mut Frame<'_>, field_area: Rect, summary_area: Rect) {
+        use ratatui::{
+            style::{Modifier, Style},
+            text::{Line, Span},
+            widgets::{Block, Borders, Padding, Paragraph},
+        };
+
+        let Some(Prompting {
+            emojis,
+            fields,
+            current,
+        }) = &self.prompting
+        else {
+            return;
+        };
+        frame.render_widget(&fields[*current].1, field_area);
+        self.last_search_area = Some(field_area);
+
+        let base = Style::default().fg(self.colors.unselected);
+        let mut lines: Vec<_> = emojis
+            .iter()
+            .map(|emoji| {
+                let glyph = match self.emoji_source {
+                    EmojiSource::InCanvas => emoji.emoji(),
+                    EmojiSource::Overlay => "",
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("{glyph:<width$}", width = EMOJI_COLUMN_WIDTH as usize),
+                        base,
+                    ),
+                    Span::styled(emoji.description(), base),
+                ])
+            })
+            .collect();
+        lines.push(Line::default());
+        for (i, (field, entry)) in fields.iter().enumerate() {
+            let style = if i == *current {
+                Style::default().fg(self.colors.selected)
+            } else {
+                base
+            };
+            lines.push(Line::styled(
+                format!("{}: {}", field.label(), entry.text()),
+                style,
+            ));
+        }
+        lines.push(Line::default());
+        lines.push(Line::styled(PROMPT_HINT, base.add_modifier(Modifier::DIM)));
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .title(PROMPT_TITLE)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.colors.border))
+                .padding(Padding::uniform(1)),
+        );
+        frame.render_widget(paragraph, summary_area);
+
+        // Nothing to pick from, but overlay frontends still paint the glyphs.
+        let x = summary_area.x.saturating_add(2);
+        let top = summary_area.y.saturating_add(2);
+        let rows = (emojis.len() as u16).min(summary_area.height.saturating_sub(3));
+        self.last_rendered_rows.clear();
+        self.last_viewport_rows = 0;
+        self.last_emoji_band = Some(Rect::new(x, top, EMOJI_COLUMN_WIDTH, rows));
+        self.last_visible_emojis = emojis
+            .iter()
+            .zip(0..rows)
+            .map(|(emoji, row)| VisibleEmoji {
+                cell: Rect::new(x, top + row, EMOJI_COLUMN_WIDTH, 1),
+                emoji: emoji.glyph(),
+            })
+            .collect();
+    }
+
     /// Index into the current filtered list of the row covering the cell at
     /// (`x`, `y`) in the last rendered frame, or `None` when no row does.
     ///
-    /// The index includes the list's scroll offset, so it addresses the same
-    /// list [`Action::PickAt`] indexes into — pointer-driven frontends can
-    /// feed the result straight back in.
+    /// The index accounts for the list's scroll offset and section headers,
+    /// so it addresses the same list [`Action::PickAt`] indexes into —
+    /// pointer-driven frontends can feed the result straight back in.
     pub fn hit_test(&self, x: u16, y: u16) -> Option<usize> {
-        let visible_index = self
-            .last_rendered_rows
+        self.last_rendered_rows
             .iter()
-            .position(|row| row.contains(Position { x, y }))?;
-
-        Some(self.last_row_offset + visible_index)
+            .find(|(row, _)| row.contains(Position { x, y }))
+            .map(|(_, index)| *index)
     }
 
     /// Cell rectangle the search box occupied in the last rendered frame,
@@ -344,6 +730,50 @@ impl<'c> App<'c> {
     }
 }
 
+/// The details being asked for after picking `emojis`: each field with
+/// what's typed in so far, and which one is being typed in.
+struct Prompting<'c> {
+    emojis: Vec<Emoji>,
+    fields: Vec<(Field, SearchEntry<'c>)>,
+    current: usize,
+}
+
+/// A detail [`App::set_prompts`] can ask for after a pick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Scope,
+    Summary,
+}
+
+impl Field {
+    fn entry(self, colors: &Colors) -> SearchEntry<'_> {
+        match self {
+            Field::Scope => SearchEntry::labelled(
+                colors,
+                "Scope (optional)",
+                "What the change is about, e.g. parser",
+            ),
+            Field::Summary => {
+                SearchEntry::labelled(colors, "Summary", "Describe the change in one line")
+            }
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Field::Scope => "Scope",
+            Field::Summary => "Summary",
+        }
+    }
+}
+
+/// How many of the most used emojis the list shows in its "Recent"
+/// section.
+const RECENT_LIMIT: usize = 5;
+const PROMPT_TITLE: &str = "Commit";
+const SUBJECT_LABEL: &str = "Subject: ";
+const PROMPT_HINT: &str = "Enter to go on, Esc to go back";
+
 pub trait Clipboard {
     type Error: std::fmt::Display;
     fn copy(&mut self, text: &str) -> Result<(), Self::Error>;
@@ -353,12 +783,20 @@ pub trait Clipboard {
 mod tests {
     use super::*;
     use crate::{colors::Colors, emoji::Emoji};
-    use ratatui::{backend::TestBackend, Terminal};
+    use ratatui::{backend::TestBackend, style::Modifier, Terminal};
 
     fn fixture() -> (&'static [Emoji], Colors) {
         (crate::emoji::EMOJIS, Colors::dark())
     }
 
+    /// The glyph of the emoji `outcome` picked.
+    fn picked(outcome: Outcome) -> String {
+        match outcome {
+            Outcome::Picked(emoji) => emoji.emoji().to_string(),
+            outcome => panic!("expected Picked, got {outcome:?}"),
+        }
+    }
+
     /// A terminal small enough that the list scrolls: far fewer rows fit
     /// than the emoji database has.
     fn terminal() -> Terminal<TestBackend> {
@@ -366,12 +804,24 @@ mod tests {
     }
 
     /// Render and hand back the emoji on the list's first visible row.
-    fn top_row(app: &mut App<'_>, terminal: &mut Terminal<TestBackend>) -> &'static str {
+    fn top_row(app: &mut App<'_>, terminal: &mut Terminal<TestBackend>) -> String {
         terminal.draw(|frame| app.render(frame)).unwrap();
         app.visible_emojis()
             .first()
             .expect("the picker rendered at least one row")
             .emoji
+            .to_string()
+    }
+
+    /// Everything the last render put in the terminal's buffer, row by row.
+    fn screen_text(terminal: &Terminal<TestBackend>) -> String {
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
     }
 
     #[test]
@@ -431,7 +881,7 @@ mod tests {
         let Outcome::Picked(s) = outcome else {
             panic!("expected Picked, got {outcome:?}");
         };
-        assert_eq!(s, emojis[0].emoji());
+        assert_eq!(s, emojis[0]);
     }
 
     #[test]
@@ -442,7 +892,7 @@ mod tests {
         let Outcome::Picked(s) = app.handle(Action::PickFocused) else {
             panic!();
         };
-        assert_eq!(s, emojis[1].emoji());
+        assert_eq!(s, emojis[1]);
     }
 
     #[test]
@@ -455,6 +905,14 @@ mod tests {
         assert_eq!(app.handle(Action::PickFocused), Outcome::Continue);
     }
 
+    #[test]
+    fn pick_focused_picks_the_best_match() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.handle(Action::SetSearch("bgfx".into()));
+        assert_eq!(picked(app.handle(Action::PickFocused)), "🐛");
+    }
+
     #[test]
     fn arrow_keys_with_no_matches_return_continue() {
         let (emojis, colors) = fixture();
@@ -471,7 +929,7 @@ mod tests {
         let (emojis, colors) = fixture();
         let mut app = App::new(emojis, &colors);
         let outcome = app.handle(Action::PickAt(2));
-        assert_eq!(outcome, Outcome::Picked(emojis[2].emoji().to_string()));
+        assert_eq!(outcome, Outcome::Picked(emojis[2].clone()));
     }
 
     #[test]
@@ -481,13 +939,13 @@ mod tests {
         app.handle(Action::PickAt(2));
         assert_eq!(
             app.handle(Action::PickFocused),
-            Outcome::Picked(emojis[2].emoji().to_string())
+            Outcome::Picked(emojis[2].clone())
         );
         // ...and the keyboard carries on from there, not from the top.
         app.handle(Action::MoveDown);
         assert_eq!(
             app.handle(Action::PickFocused),
-            Outcome::Picked(emojis[3].emoji().to_string())
+            Outcome::Picked(emojis[3].clone())
         );
     }
 
@@ -499,7 +957,7 @@ mod tests {
         app.handle(Action::PickAt(emojis.len() + 100));
         assert_eq!(
             app.handle(Action::PickFocused),
-            Outcome::Picked(emojis[1].emoji().to_string())
+            Outcome::Picked(emojis[1].clone())
         );
     }
 
@@ -588,7 +1046,26 @@ mod tests {
         let Outcome::Picked(focused) = app.handle(Action::PickFocused) else {
             panic!("the picker has a selection");
         };
-        assert!(app.visible_emojis().iter().any(|ve| ve.emoji == focused));
+        assert!(app
+            .visible_emojis()
+            .iter()
+            .any(|ve| ve.emoji == focused.emoji()));
+    }
+
+    #[test]
+    fn scroll_in_a_one_row_window_steps_off_section_headers() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_suggested(vec!["🐛".into()]);
+        let mut terminal = Terminal::new(TestBackend::new(60, 11)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert_eq!(app.last_viewport_rows, 1);
+
+        // Onto the "Suggested" header, then the "All" one.
+        app.handle(Action::Scroll(-1));
+        assert_eq!(top_row(&mut app, &mut terminal), "🐛");
+        app.handle(Action::Scroll(1));
+        assert_eq!(top_row(&mut app, &mut terminal), emojis[0].emoji());
     }
 
     #[test]
@@ -598,7 +1075,7 @@ mod tests {
         assert_eq!(app.handle(Action::Scroll(5)), Outcome::Continue);
         assert_eq!(
             app.handle(Action::PickFocused),
-            Outcome::Picked(emojis[0].emoji().to_string())
+            Outcome::Picked(emojis[0].clone())
         );
     }
 
@@ -637,19 +1114,375 @@ mod tests {
         }
         terminal.draw(|frame| app.render(frame)).unwrap();
 
-        let first = *app
+        let first = app
             .visible_emojis()
             .first()
+            .cloned()
             .expect("the picker rendered at least one row");
         let index = app
             .hit_test(first.cell.x, first.cell.y)
             .expect("the first visible row is hit-testable");
-        assert_eq!(
-            app.handle(Action::PickAt(index)),
-            Outcome::Picked(first.emoji.to_string())
-        );
+        assert_eq!(picked(app.handle(Action::PickAt(index))), *first.emoji);
         assert_ne!(index, 0, "the table should have scrolled off the first row");
         // The search box sits above the list, so nothing is hit there.
         assert!(app.hit_test(0, 0).is_none());
     }
+
+    #[test]
+    fn semver_column_is_only_shown_on_request() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        let mut terminal = terminal();
+        app.handle(Action::SetSearch(":sparkles:".into()));
+
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert!(!screen_text(&terminal).contains("minor"));
+
+        app.set_show_semver(true);
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert!(screen_text(&terminal).contains("minor"));
+    }
+
+    #[test]
+    fn matched_characters_are_highlighted_in_both_emoji_modes() {
+        let (emojis, colors) = fixture();
+        for mut app in [
+            App::new(emojis, &colors),
+            App::with_emoji_overlay(emojis, &colors),
+        ] {
+            let mut terminal = terminal();
+            app.handle(Action::SetSearch("bgfx".into()));
+            terminal.draw(|frame| app.render(frame)).unwrap();
+
+            // The best match, `:bug:` "Fix a bug.", tops the list with its
+            // `b`, `g`, `F` and `x` picked out.
+            let row = app.visible_emojis()[0].cell.y;
+            let buffer = terminal.backend().buffer();
+            let underlined: String = (0..buffer.area.width)
+                .map(|x| &buffer[(x, row)])
+                .filter(|cell| cell.modifier.contains(Modifier::UNDERLINED))
+                .map(|cell| cell.symbol())
+                .collect();
+            assert_eq!(underlined, "bgFx");
+        }
+    }
+
+    fn history_of(picks: &[&str]) -> Vec<HistoryEntry> {
+        let mut entries = Vec::new();
+        for emoji in picks {
+            history::record(&mut entries, emoji);
+        }
+        entries
+    }
+
+    #[test]
+    fn history_lists_the_most_used_emojis_first() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_history(history_of(&["✨", "🐛", "🐛"]));
+        let mut terminal = terminal();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Recent"), "{screen}");
+        assert!(screen.contains("All"), "{screen}");
+        let visible: Vec<_> = app.visible_emojis().iter().map(|ve| &*ve.emoji).collect();
+        assert_eq!(visible[..3], ["🐛", "✨", emojis[0].emoji()]);
+        // The headers take up rows of their own.
+        let cells: Vec<_> = app.visible_emojis().iter().map(|ve| ve.cell.y).collect();
+        assert_eq!(cells[2], cells[1] + 2);
+
+        assert_eq!(picked(app.handle(Action::PickFocused)), "🐛");
+        app.handle(Action::MoveDown);
+        app.handle(Action::MoveDown);
+        assert_eq!(
+            app.handle(Action::PickFocused),
+            Outcome::Picked(emojis[0].clone())
+        );
+    }
+
+    #[test]
+    fn suggestions_come_before_the_history() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_history(history_of(&["✨", "📝"]));
+        app.set_suggested(vec!["📝".into(), "🔥".into()]);
+        let mut terminal = terminal();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+
+        let screen = screen_text(&terminal);
+        let suggested = screen.find("Suggested").expect("a Suggested header");
+        assert!(screen
+            .find("Recent")
+            .is_some_and(|recent| recent > suggested));
+        let visible: Vec<_> = app.visible_emojis().iter().map(|ve| &*ve.emoji).collect();
+        // Each emoji is listed once, under the first section it's in.
+        assert_eq!(visible[..3], ["📝", "🔥", "✨"]);
+
+        assert_eq!(picked(app.handle(Action::PickFocused)), "📝");
+    }
+
+    #[test]
+    fn emojis_can_be_grouped_by_category() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_grouped(true);
+        app.set_history(history_of(&["🐛"]));
+        let mut terminal = Terminal::new(TestBackend::new(60, 40)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+
+        let screen = screen_text(&terminal);
+        let header = |title| screen.find(title).unwrap_or_else(|| panic!("{title}"));
+        assert!(header("Recent") < header("Features"));
+        assert!(header("Features") < header("Fixes"));
+        assert!(!screen.contains("All"), "{screen}");
+        let visible: Vec<_> = app.visible_emojis().iter().map(|ve| &*ve.emoji).collect();
+        assert_eq!(visible[..3], ["🐛", "✨", "💄"]);
+        // Fixes come after all the features, without the recent bug.
+        let ambulance = visible.iter().position(|&e| e == "🚑️").unwrap();
+        assert_eq!(visible[ambulance - 1], "🔌");
+
+        // Searching lists the matches in one run again.
+        app.handle(Action::SetSearch("cat:fix".into()));
+        assert_eq!(top_row(&mut app, &mut terminal), "🐛");
+        assert!(!screen_text(&terminal).contains("Fixes"));
+    }
+
+    #[test]
+    fn the_subject_hints_at_emojis_and_shows_above_the_search() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_suggested(vec!["📝".into()]);
+        app.set_subject("fix crash in parser");
+        let mut terminal = terminal();
+
+        assert_eq!(top_row(&mut app, &mut terminal), "🐛");
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Subject: fix crash in parser"), "{screen}");
+        let visible: Vec<_> = app.visible_emojis().iter().map(|ve| &*ve.emoji).collect();
+        assert_eq!(visible[..3], ["🐛", "🩹", "📝"]);
+        assert_eq!(picked(app.handle(Action::PickFocused)), "🐛");
+    }
+
+    #[test]
+    fn the_preview_follows_the_selection() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        let files = (1..=7).map(|i| format!("M src/{i}.rs")).collect();
+        app.set_subject("Fix it");
+        app.set_preview(Some(Preview::new("Fix it", files)));
+        let mut terminal = Terminal::new(TestBackend::new(60, 30)).unwrap();
+
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Preview"), "{screen}");
+        assert!(!screen.contains("Subject: "), "{screen}");
+        // The subject hints at 🐛 and 🩹, which come first. Wide glyphs take
+        // up an extra cell.
+        assert!(screen.contains("🐛  Fix it"), "{screen}");
+        assert!(screen.contains("M src/5.rs"));
+        assert!(!screen.contains("M src/6.rs"));
+        assert!(screen.contains("… and 2 more"));
+
+        // Toggled emojis take the selected one's place.
+        app.handle(Action::MoveDown);
+        app.handle(Action::Toggle);
+        app.handle(Action::MoveDown);
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert!(screen_text(&terminal).contains("🩹  Fix it"));
+    }
+
+    #[test]
+    fn hit_test_skips_section_headers() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_history(history_of(&["🐛"]));
+        let mut terminal = terminal();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+
+        let [first, second] = [0, 1].map(|i| app.visible_emojis()[i].clone());
+        // The "All" header sits between the two.
+        assert!(app.hit_test(first.cell.x, first.cell.y + 1).is_none());
+        let index = app.hit_test(second.cell.x, second.cell.y).unwrap();
+        assert_eq!(picked(app.handle(Action::PickAt(index))), *second.emoji);
+    }
+
+    #[test]
+    fn history_is_ignored_while_searching() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_history(history_of(&["✨"]));
+        app.handle(Action::SetSearch("bug".into()));
+        let mut terminal = terminal();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+
+        assert!(!screen_text(&terminal).contains("Recent"));
+        assert_eq!(app.visible_emojis()[0].emoji, "🐛");
+    }
+
+    #[test]
+    fn picks_are_recorded_without_reordering_the_list() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.handle(Action::PickAt(3));
+        app.handle(Action::PickFocused);
+
+        assert_eq!(app.history(), history_of(&[emojis[3].emoji(); 2]));
+        assert_eq!(
+            app.handle(Action::PickAt(0)),
+            Outcome::Picked(emojis[0].clone())
+        );
+    }
+
+    #[test]
+    fn runtime_emojis_are_listed_and_picked() {
+        let colors = Colors::dark();
+        let mut emojis = crate::emoji::EMOJIS.to_vec();
+        emojis.push(Emoji::new("🦀", ":crab:", "Rust-specific change."));
+        let mut app = App::new(emojis, &colors);
+        app.handle(Action::SetSearch("rust".into()));
+
+        let mut terminal = terminal();
+        assert_eq!(top_row(&mut app, &mut terminal), "🦀");
+        assert_eq!(picked(app.handle(Action::PickFocused)), "🦀");
+    }
+
+    #[test]
+    fn select_moves_onto_the_emoji_with_the_code() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_history(history_of(&["✨"]));
+
+        assert!(app.select(":bug:"));
+        assert_eq!(picked(app.handle(Action::PickFocused)), "🐛");
+        // It's scrolled into view, too.
+        let mut terminal = terminal();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert!(app.visible_emojis().iter().any(|ve| ve.emoji == "🐛"));
+
+        assert!(!app.select(":no_such_emoji:"));
+        assert_eq!(picked(app.handle(Action::PickFocused)), "🐛");
+    }
+
+    #[test]
+    fn prompts_ask_for_details_after_a_pick() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_prompts(true, true);
+
+        assert_eq!(app.handle(Action::PickFocused), Outcome::Continue);
+        for c in "core".chars() {
+            app.handle(Action::Append(c));
+        }
+        assert_eq!(app.input_text(), "core");
+        assert_eq!(app.search_text(), "");
+        assert_eq!(app.handle(Action::PickFocused), Outcome::Continue);
+        app.handle(Action::SetSearch("Fix it ".into()));
+        let mut terminal = terminal();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Summary: Fix it"), "{screen}");
+        assert!(screen.contains("Scope: core"), "{screen}");
+
+        let details = Details {
+            scope: Some("core".to_string()),
+            summary: "Fix it".to_string(),
+        };
+        assert_eq!(
+            app.handle(Action::PickFocused),
+            Outcome::Described(vec![emojis[0].clone()], details)
+        );
+        assert_eq!(app.history(), history_of(&[emojis[0].emoji()]));
+        // Back to picking.
+        assert_eq!(app.handle(Action::Append('x')), Outcome::Continue);
+        assert_eq!(app.search_text(), "x");
+    }
+
+    #[test]
+    fn prompts_can_be_skipped_and_backed_out_of() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_prompts(true, false);
+
+        app.handle(Action::PickAt(2));
+        app.handle(Action::Append('x'));
+        // Back to the list, with the pick still selected.
+        app.handle(Action::Cancel);
+        assert!(app.history().is_empty());
+        app.handle(Action::PickFocused);
+        assert_eq!(app.input_text(), "");
+        assert_eq!(
+            app.handle(Action::PickFocused),
+            Outcome::Described(vec![emojis[2].clone()], Details::default())
+        );
+    }
+
+    #[test]
+    fn toggled_emojis_are_picked_together_in_order() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+
+        app.handle(Action::MoveDown);
+        app.handle(Action::MoveDown);
+        app.handle(Action::Toggle);
+        app.handle(Action::MoveUp);
+        app.handle(Action::Toggle);
+        // Toggling twice untoggles.
+        app.handle(Action::MoveUp);
+        app.handle(Action::Toggle);
+        app.handle(Action::Toggle);
+        assert_eq!(
+            app.handle(Action::PickFocused),
+            Outcome::PickedMany(vec![emojis[2].clone(), emojis[1].clone()])
+        );
+        assert_eq!(app.history().len(), 2);
+        // The next pick starts afresh.
+        assert_eq!(picked(app.handle(Action::PickFocused)), emojis[0].emoji());
+    }
+
+    #[test]
+    fn toggles_survive_searches_and_show_in_the_gutter() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        let mut terminal = terminal();
+
+        app.handle(Action::Toggle);
+        app.handle(Action::SetSearch(emojis[3].code().into()));
+        app.handle(Action::Toggle);
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("(2 toggled)"));
+        assert!(screen.contains("❯•"));
+
+        app.handle(Action::ClearSearch);
+        assert_eq!(
+            app.handle(Action::PickFocused),
+            Outcome::PickedMany(vec![emojis[0].clone(), emojis[3].clone()])
+        );
+    }
+
+    #[test]
+    fn toggled_emojis_are_described_together() {
+        let (emojis, colors) = fixture();
+        let mut app = App::new(emojis, &colors);
+        app.set_prompts(false, true);
+
+        app.handle(Action::Toggle);
+        app.handle(Action::MoveDown);
+        app.handle(Action::Toggle);
+        app.handle(Action::PickFocused);
+        assert!(app.is_prompting());
+        // Backing out keeps the toggles.
+        app.handle(Action::Cancel);
+        app.handle(Action::PickFocused);
+        app.handle(Action::SetSearch("Fix it".into()));
+        let details = Details {
+            scope: None,
+            summary: "Fix it".to_string(),
+        };
+        assert_eq!(
+            app.handle(Action::PickFocused),
+            Outcome::Described(vec![emojis[0].clone(), emojis[1].clone()], details)
+        );
+    }
 }
@@ -4,6 +4,8 @@ pub struct Colors {
     pub selected: Color,
     pub unselected: Color,
     pub border: Color,
+    /// The parts of a row's code and description that matched the search.
+    pub matched: Color,
 }
 
 impl Colors {
@@ -12,6 +14,7 @@ impl Colors {
             selected: Color::Green,
             unselected: Color::DarkGray,
             border: Color::DarkGray,
+            matched: Color::Blue,
         }
     }
 
@@ -20,6 +23,7 @@ impl Colors {
             selected: Color::Green,
             unselected: Color::White,
             border: Color::White,
+            matched: Color::Yellow,
         }
     }
 }
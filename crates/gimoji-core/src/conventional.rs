@@ -0,0 +1,107 @@
+/// The `type(scope)!: ` header a [Conventional Commits] subject starts with.
+///
+/// [Conventional Commits]: https://www.conventionalcommits.org/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConventionalHeader<'s> {
+    commit_type: &'s str,
+    scope: Option<&'s str>,
+    breaking: bool,
+    end: usize,
+}
+
+impl<'s> ConventionalHeader<'s> {
+    /// The header `subject` starts with, if it has one.
+    pub fn parse(subject: &'s str) -> Option<Self> {
+        let type_len = subject
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+            .unwrap_or(subject.len());
+        let commit_type = &subject[..type_len];
+        if !commit_type.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let mut rest = &subject[type_len..];
+        let mut scope = None;
+        if let Some(after) = rest.strip_prefix('(') {
+            let end = after.find([')', '\n'])?;
+            if !after[end..].starts_with(')') || after[..end].trim().is_empty() {
+                return None;
+            }
+            scope = Some(&after[..end]);
+            rest = &after[end + 1..];
+        }
+        let breaking = match rest.strip_prefix('!') {
+            Some(after) => {
+                rest = after;
+                true
+            }
+            None => false,
+        };
+        let rest = rest.strip_prefix(": ")?.trim_start_matches(' ');
+
+        Some(Self {
+            commit_type,
+            scope,
+            breaking,
+            end: subject.len() - rest.len(),
+        })
+    }
+
+    /// The type, e.g. `feat`.
+    pub fn commit_type(&self) -> &'s str {
+        self.commit_type
+    }
+
+    /// The scope, without the parentheses.
+    pub fn scope(&self) -> Option<&'s str> {
+        self.scope
+    }
+
+    /// Whether the `!` marks a breaking change.
+    pub fn is_breaking(&self) -> bool {
+        self.breaking
+    }
+
+    /// Byte offset of the rest of the subject, past the header and the
+    /// spaces after it.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headers_parse() {
+        let header = ConventionalHeader::parse("feat(core)!: ✨ Add it").unwrap();
+        assert_eq!(header.commit_type(), "feat");
+        assert_eq!(header.scope(), Some("core"));
+        assert!(header.is_breaking());
+        assert_eq!(&"feat(core)!: ✨ Add it"[header.end()..], "✨ Add it");
+
+        let header = ConventionalHeader::parse("fix:  Fix it").unwrap();
+        assert_eq!(header.commit_type(), "fix");
+        assert_eq!(header.scope(), None);
+        assert!(!header.is_breaking());
+        assert_eq!(header.end(), "fix:  ".len());
+    }
+
+    #[test]
+    fn other_subjects_have_no_header() {
+        for subject in [
+            "✨ Add it",
+            "Add it",
+            "feat:no space",
+            "feat(): Add it",
+            "feat(core: Add it",
+            "feat(co\nre): Add it",
+            "1feat: Add it",
+            ":sparkles: Add it",
+            "",
+        ] {
+            assert_eq!(ConventionalHeader::parse(subject), None, "{subject:?}");
+        }
+    }
+}
@@ -1,70 +1,221 @@
-#[derive(Debug)]
+use std::borrow::Cow;
+
+/// An emoji the picker offers: either one of the database's, baked into
+/// [`EMOJIS`] at build time, or one made at runtime with [`Emoji::new`],
+/// e.g. from a project's config.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Emoji {
-    code: &'static str,
-    description: &'static str,
-    emoji: &'static str,
-    entity: &'static str,
-    name: &'static str,
+    code: Cow<'static, str>,
+    description: Cow<'static, str>,
+    emoji: Cow<'static, str>,
+    entity: Cow<'static, str>,
+    name: Cow<'static, str>,
+    semver: Option<SemverBump>,
+    commit_type: Option<Cow<'static, str>>,
+    category: Option<Cow<'static, str>>,
 }
 
 impl Emoji {
-    /// Case-insensitive ASCII substring match against any of the emoji's
-    /// searchable fields. `needle_lower` must already be lowercased by the
-    /// caller (typically once per filter pass, not once per emoji).
-    pub fn contains(&self, needle_lower: &str) -> bool {
-        contains_ignore_ascii_case(self.code, needle_lower)
-            || contains_ignore_ascii_case(self.description, needle_lower)
-            || self.emoji.contains(needle_lower)
-            || contains_ignore_ascii_case(self.entity, needle_lower)
-            || contains_ignore_ascii_case(self.name, needle_lower)
+    /// An emoji that isn't in the database, given its glyph, its `:code:`
+    /// (the colons are optional) and a description of the changes it marks.
+    pub fn new(
+        emoji: impl Into<Cow<'static, str>>,
+        code: impl Into<Cow<'static, str>>,
+        description: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        let emoji = emoji.into();
+        let code = code.into();
+        let name = code.trim_matches(':').to_string();
+        let code = if code.starts_with(':') && code.ends_with(':') && code.len() > 1 {
+            code
+        } else {
+            Cow::Owned(format!(":{name}:"))
+        };
+        let entity = emoji
+            .chars()
+            .next()
+            .map(|c| format!("&#x{:x};", u32::from(c)))
+            .unwrap_or_default();
+
+        Self {
+            code,
+            description: description.into(),
+            emoji,
+            entity: entity.into(),
+            name: name.into(),
+            semver: None,
+            commit_type: None,
+            category: None,
+        }
+    }
+
+    /// Set how a change tagged with this emoji bumps the version.
+    pub fn with_semver(mut self, semver: Option<SemverBump>) -> Self {
+        self.semver = semver;
+        self
+    }
+
+    /// Set the Conventional Commits type of changes tagged with this emoji.
+    pub fn with_commit_type(mut self, commit_type: Option<Cow<'static, str>>) -> Self {
+        self.commit_type = commit_type;
+        self
+    }
+
+    /// Set the category the emoji is listed under, e.g. `deps`; see
+    /// [`CATEGORIES`].
+    pub fn with_category(mut self, category: Option<Cow<'static, str>>) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn emoji(&self) -> &str {
+        &self.emoji
+    }
+
+    /// The bare gitmoji name, e.g. `bug` for `:bug:`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// How a change tagged with this emoji bumps the version, as gitmoji's
+    /// `semver` field has it. `None` for emojis that don't affect the
+    /// version on their own (docs, tests, tooling…).
+    pub fn semver(&self) -> Option<SemverBump> {
+        self.semver
     }
 
-    pub fn code(&self) -> &'static str {
-        self.code
+    /// The Conventional Commits type of changes tagged with this emoji, e.g.
+    /// `fix` for `:bug:`. `None` for emojis that don't map to one.
+    pub fn commit_type(&self) -> Option<&str> {
+        self.commit_type.as_deref()
     }
 
-    pub fn description(&self) -> &'static str {
-        self.description
+    /// The kind of change the emoji marks, e.g. `fixes` for `:bug:`, which
+    /// the picker can group emojis by. `None` for emojis made without one.
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
     }
 
-    pub fn emoji(&self) -> &'static str {
-        self.emoji
+    /// The glyph, without copying it for the database's emojis.
+    pub(crate) fn glyph(&self) -> Cow<'static, str> {
+        self.emoji.clone()
     }
 }
 
-fn contains_ignore_ascii_case(haystack: &str, needle_lower: &str) -> bool {
-    if needle_lower.is_empty() {
-        return true;
+/// The part of a semantic version a change bumps.
+///
+/// Ordered by significance, so the bump a set of changes calls for is simply
+/// the `max` of theirs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SemverBump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl SemverBump {
+    /// The lowercase name gitmoji uses for the bump in its database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SemverBump::Patch => "patch",
+            SemverBump::Minor => "minor",
+            SemverBump::Major => "major",
+        }
     }
-    if needle_lower.len() > haystack.len() {
-        return false;
+}
+
+impl std::str::FromStr for SemverBump {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "patch" => Ok(SemverBump::Patch),
+            "minor" => Ok(SemverBump::Minor),
+            "major" => Ok(SemverBump::Major),
+            _ => Err(format!(
+                "unknown semver bump `{s}` (expected `patch`, `minor` or `major`)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for SemverBump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
-    haystack
-        .as_bytes()
-        .windows(needle_lower.len())
-        .any(|w| w.eq_ignore_ascii_case(needle_lower.as_bytes()))
 }
 
+/// The categories of the database's emojis, in the order the picker lists
+/// them when grouping.
+pub const CATEGORIES: &[&str] = &[
+    "features",
+    "fixes",
+    "refactoring",
+    "docs",
+    "testing",
+    "deps",
+    "tooling",
+    "infra",
+    "workflow",
+];
+
 include!(concat!(env!("OUT_DIR"), "/emojis.rs"));
 
 #[cfg(test)]
 mod tests {
-    use super::contains_ignore_ascii_case;
+    use super::{Emoji, SemverBump, CATEGORIES, EMOJIS};
+
+    #[test]
+    fn semver_is_baked_from_the_database() {
+        let find = |code| EMOJIS.iter().find(|e| e.code() == code).unwrap();
+        assert_eq!(find(":boom:").semver(), Some(SemverBump::Major));
+        assert_eq!(find(":sparkles:").semver(), Some(SemverBump::Minor));
+        assert_eq!(find(":bug:").semver(), Some(SemverBump::Patch));
+        assert_eq!(find(":memo:").semver(), None);
+    }
 
     #[test]
-    fn ascii_case_insensitive_match() {
-        assert!(contains_ignore_ascii_case("Sparkles", "spark"));
-        assert!(contains_ignore_ascii_case("Sparkles", "KLE"));
-        assert!(!contains_ignore_ascii_case("Sparkles", "fire"));
+    fn every_database_emoji_has_a_known_category() {
+        for emoji in EMOJIS {
+            let category = emoji.category().unwrap_or_default();
+            assert!(CATEGORIES.contains(&category), "{}", emoji.code());
+        }
+        let find = |code| EMOJIS.iter().find(|e| e.code() == code).unwrap();
+        assert_eq!(find(":arrow_up:").category(), Some("deps"));
+    }
+
+    #[test]
+    fn runtime_emojis_fill_in_the_name_and_colons() {
+        let crab = Emoji::new("🦀", "crab", "Rust-specific change.");
+        assert_eq!((crab.code(), crab.name()), (":crab:", "crab"));
+        assert_eq!(crab.entity, "&#x1f980;");
+        assert_eq!(crab.semver(), None);
+
+        let auth =
+            Emoji::new("🔐", ":auth:", "Authentication.").with_semver(Some(SemverBump::Minor));
+        assert_eq!((auth.code(), auth.name()), (":auth:", "auth"));
+        assert_eq!(auth.semver(), Some(SemverBump::Minor));
     }
 
     #[test]
-    fn empty_needle_matches() {
-        assert!(contains_ignore_ascii_case("anything", ""));
+    fn semver_bumps_order_by_significance() {
+        assert!(SemverBump::Major > SemverBump::Minor);
+        assert!(SemverBump::Minor > SemverBump::Patch);
     }
 
     #[test]
-    fn needle_longer_than_haystack_does_not_match() {
-        assert!(!contains_ignore_ascii_case("hi", "hello"));
+    fn semver_bumps_parse_from_their_names() {
+        for bump in [SemverBump::Patch, SemverBump::Minor, SemverBump::Major] {
+            assert_eq!(bump.as_str().parse(), Ok(bump));
+        }
+        assert!("huge".parse::<SemverBump>().is_err());
     }
 }
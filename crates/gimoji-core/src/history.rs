@@ -0,0 +1,141 @@
+/// Storage for the emojis picked in earlier sessions, so the picker can
+/// offer the ones used most at the top of the list.
+///
+/// Frontends back this with whatever persists on their platform: the
+/// native binary with a file, the web build with `localStorage`. [`parse`]
+/// and [`format`] give them a shared text form to store.
+pub trait History {
+    type Error: std::fmt::Display;
+    /// Read back the history, or an empty one if nothing was saved yet.
+    fn load(&mut self) -> Result<Vec<HistoryEntry>, Self::Error>;
+    fn save(&mut self, entries: &[HistoryEntry]) -> Result<(), Self::Error>;
+}
+
+/// How often an emoji was picked, and how recently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The emoji glyph.
+    pub emoji: String,
+    /// Number of times it was picked.
+    pub count: u32,
+    /// When it was last picked, as a sequence number: the most recent pick
+    /// across the whole history has the highest.
+    pub last_used: u64,
+}
+
+/// Count a pick of `emoji` in `entries`.
+pub fn record(entries: &mut Vec<HistoryEntry>, emoji: &str) {
+    let last_used = entries.iter().map(|e| e.last_used).max().unwrap_or(0) + 1;
+    match entries.iter_mut().find(|e| e.emoji == emoji) {
+        Some(entry) => {
+            entry.count = entry.count.saturating_add(1);
+            entry.last_used = last_used;
+        }
+        None => entries.push(HistoryEntry {
+            emoji: emoji.to_string(),
+            count: 1,
+            last_used,
+        }),
+    }
+}
+
+/// Up to `limit` emojis from `entries`, most picked first; ties go to the
+/// one picked most recently.
+pub fn most_used(entries: &[HistoryEntry], limit: usize) -> Vec<&str> {
+    let mut ranked: Vec<&HistoryEntry> = entries.iter().collect();
+    ranked.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| b.last_used.cmp(&a.last_used))
+    });
+
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|e| e.emoji.as_str())
+        .collect()
+}
+
+/// Read entries back from the text [`format`] writes. Lines that don't
+/// parse are skipped rather than failing the lot: a damaged history should
+/// cost the picker its ordering, not the user their pick.
+pub fn parse(text: &str) -> Vec<HistoryEntry> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let count = fields.next()?.parse().ok()?;
+            let last_used = fields.next()?.parse().ok()?;
+            let emoji = fields.next().filter(|e| !e.is_empty())?;
+
+            Some(HistoryEntry {
+                emoji: emoji.to_string(),
+                count,
+                last_used,
+            })
+        })
+        .collect()
+}
+
+/// Render entries as text, one tab-separated `count`, `last_used` and
+/// `emoji` line each.
+pub fn format(entries: &[HistoryEntry]) -> String {
+    entries
+        .iter()
+        .map(|e| format!("{}\t{}\t{}\n", e.count, e.last_used, e.emoji))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picks(emojis: &[&str]) -> Vec<HistoryEntry> {
+        let mut entries = Vec::new();
+        for emoji in emojis {
+            record(&mut entries, emoji);
+        }
+        entries
+    }
+
+    #[test]
+    fn recording_counts_picks() {
+        let entries = picks(&["🐛", "✨", "🐛"]);
+        assert_eq!(
+            entries,
+            [
+                HistoryEntry {
+                    emoji: "🐛".into(),
+                    count: 2,
+                    last_used: 3
+                },
+                HistoryEntry {
+                    emoji: "✨".into(),
+                    count: 1,
+                    last_used: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn most_used_ranks_by_count_then_recency() {
+        let entries = picks(&["🐛", "✨", "📝", "🐛", "📝", "🎨"]);
+        assert_eq!(most_used(&entries, 10), ["📝", "🐛", "🎨", "✨"]);
+        assert_eq!(most_used(&entries, 2), ["📝", "🐛"]);
+        assert!(most_used(&[], 5).is_empty());
+    }
+
+    #[test]
+    fn format_and_parse_round_trip() {
+        let entries = picks(&["🐛", "🧑‍💻", "🐛"]);
+        assert_eq!(format(&entries), "2\t3\t🐛\n1\t2\t🧑‍💻\n");
+        assert_eq!(parse(&format(&entries)), entries);
+    }
+
+    #[test]
+    fn damaged_lines_are_skipped() {
+        let text = "2\t3\t🐛\ngarbage\nx\t1\t✨\n1\t2\t\n1\t4\t📝";
+        let emojis: Vec<_> = parse(text).into_iter().map(|e| e.emoji).collect();
+        assert_eq!(emojis, ["🐛", "📝"]);
+    }
+}
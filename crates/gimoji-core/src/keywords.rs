@@ -0,0 +1,161 @@
+use crate::emoji::{Emoji, EMOJIS};
+
+/// The emojis the words of a commit `subject` hint at, e.g. 🐛 for "fix
+/// crash in parser", in the order the words come in.
+///
+/// Words match keywords case-insensitively, plurals and past tenses
+/// included ("fixes", "fixed"), and the type and scope of a Conventional
+/// Commits header (`fix(parser): `) count as words too.
+pub fn hinted(subject: &str) -> Vec<&'static Emoji> {
+    let mut emojis: Vec<&'static Emoji> = Vec::new();
+    let words = subject
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase);
+    for word in words {
+        let codes = KEYWORDS
+            .iter()
+            .filter(|(keyword, _)| is_form_of(&word, keyword))
+            .map(|(_, code)| code);
+        for code in codes {
+            let Some(emoji) = EMOJIS.iter().find(|emoji| emoji.code() == *code) else {
+                continue;
+            };
+            if !emojis.contains(&emoji) {
+                emojis.push(emoji);
+            }
+        }
+    }
+
+    emojis
+}
+
+/// Whether `word` is `keyword` itself or one with a common English ending.
+fn is_form_of(word: &str, keyword: &str) -> bool {
+    match word.strip_prefix(keyword) {
+        Some(ending) => ENDINGS.contains(&ending),
+        None => false,
+    }
+}
+
+const ENDINGS: [&str; 6] = ["", "s", "es", "d", "ed", "ing"];
+
+/// Words of commit subjects and the `:code:`s of the emojis they hint at,
+/// the likelier ones first.
+const KEYWORDS: &[(&str, &str)] = &[
+    ("fix", ":bug:"),
+    ("fix", ":adhesive_bandage:"),
+    ("bug", ":bug:"),
+    ("crash", ":bug:"),
+    ("hotfix", ":ambulance:"),
+    ("critical", ":ambulance:"),
+    ("typo", ":pencil2:"),
+    ("revert", ":rewind:"),
+    ("deps", ":arrow_up:"),
+    ("dependency", ":arrow_up:"),
+    ("dependencies", ":arrow_up:"),
+    ("bump", ":arrow_up:"),
+    ("upgrade", ":arrow_up:"),
+    ("downgrade", ":arrow_down:"),
+    ("pin", ":pushpin:"),
+    ("doc", ":memo:"),
+    ("docs", ":memo:"),
+    ("documentation", ":memo:"),
+    ("readme", ":memo:"),
+    ("comment", ":bulb:"),
+    ("test", ":white_check_mark:"),
+    ("tests", ":white_check_mark:"),
+    ("remove", ":fire:"),
+    ("delete", ":fire:"),
+    ("dead", ":coffin:"),
+    ("refactor", ":recycle:"),
+    ("cleanup", ":recycle:"),
+    ("perf", ":zap:"),
+    ("performance", ":zap:"),
+    ("speed", ":zap:"),
+    ("optimize", ":zap:"),
+    ("feat", ":sparkles:"),
+    ("feature", ":sparkles:"),
+    ("add", ":sparkles:"),
+    ("implement", ":sparkles:"),
+    ("format", ":art:"),
+    ("style", ":art:"),
+    ("lint", ":rotating_light:"),
+    ("warning", ":rotating_light:"),
+    ("clippy", ":rotating_light:"),
+    ("ci", ":construction_worker:"),
+    ("ci", ":green_heart:"),
+    ("build", ":construction_worker:"),
+    ("security", ":lock:"),
+    ("secret", ":closed_lock_with_key:"),
+    ("release", ":bookmark:"),
+    ("version", ":bookmark:"),
+    ("deploy", ":rocket:"),
+    ("wip", ":construction:"),
+    ("config", ":wrench:"),
+    ("configuration", ":wrench:"),
+    ("script", ":hammer:"),
+    ("rename", ":truck:"),
+    ("move", ":truck:"),
+    ("merge", ":twisted_rightwards_arrows:"),
+    ("init", ":tada:"),
+    ("initial", ":tada:"),
+    ("log", ":loud_sound:"),
+    ("logging", ":loud_sound:"),
+    ("ui", ":lipstick:"),
+    ("css", ":lipstick:"),
+    ("type", ":label:"),
+    ("types", ":label:"),
+    ("i18n", ":globe_with_meridians:"),
+    ("translation", ":globe_with_meridians:"),
+    ("accessibility", ":wheelchair:"),
+    ("a11y", ":wheelchair:"),
+    ("license", ":page_facing_up:"),
+    ("breaking", ":boom:"),
+    ("database", ":card_file_box:"),
+    ("migration", ":card_file_box:"),
+    ("gitignore", ":see_no_evil:"),
+    ("experiment", ":alembic:"),
+    ("seo", ":mag:"),
+    ("auth", ":passport_control:"),
+    ("deprecate", ":wastebasket:"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(subject: &str) -> Vec<&str> {
+        hinted(subject).iter().map(|emoji| emoji.code()).collect()
+    }
+
+    #[test]
+    fn words_hint_at_emojis_in_order() {
+        assert_eq!(
+            codes("fix crash in parser"),
+            [":bug:", ":adhesive_bandage:"]
+        );
+        assert_eq!(
+            codes("Fixed a typo"),
+            [":bug:", ":adhesive_bandage:", ":pencil2:"]
+        );
+        assert_eq!(codes("docs: Bump deps"), [":memo:", ":arrow_up:"]);
+        assert_eq!(
+            codes("Reverting the tests"),
+            [":rewind:", ":white_check_mark:"]
+        );
+        assert!(codes("Frobnicate the widget").is_empty());
+        // Only whole words count.
+        assert!(codes("prefix the fixture").is_empty());
+    }
+
+    #[test]
+    fn every_keyword_has_its_emoji() {
+        for (keyword, code) in KEYWORDS {
+            assert!(
+                EMOJIS.iter().any(|emoji| emoji.code() == *code),
+                "{keyword}: {code}"
+            );
+        }
+    }
+}
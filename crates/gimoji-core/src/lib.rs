@@ -1,15 +1,32 @@
+extern crate alloc;
 extern crate self as gimoji_core;
 
 pub mod app;
 pub mod colors;
+pub mod conventional;
 pub mod emoji;
+pub mod history;
+pub mod keywords;
+pub mod lookup;
+pub mod prefix;
+pub mod preview;
+pub mod query;
+pub mod search;
 pub mod search_entry;
 pub mod selection_view;
+pub mod template;
 pub mod toast;
 
-pub use app::{Action, App, Clipboard, Outcome, VisibleEmoji};
+pub use app::{Action, App, Clipboard, Details, Outcome, VisibleEmoji};
 pub use colors::Colors;
-pub use emoji::{Emoji, EMOJIS};
+pub use conventional::ConventionalHeader;
+pub use emoji::{Emoji, SemverBump, EMOJIS};
+pub use history::{History, HistoryEntry};
+pub use lookup::{lookup, UnknownEmoji};
+pub use prefix::{detect_prefix, detect_prefix_with};
+pub use preview::Preview;
+pub use query::Query;
 pub use search_entry::SearchEntry;
 pub use selection_view::{FilteredView, SelectionView};
+pub use template::{Template, TemplateError};
 pub use toast::Toast;
@@ -0,0 +1,196 @@
+use std::fmt;
+
+use crate::{
+    emoji::{Emoji, EMOJIS},
+    prefix::detect_prefix,
+};
+
+/// Find the emoji `query` names exactly: by glyph (variation selectors are
+/// optional), by `:code:` or by bare name, the latter two ignoring ASCII
+/// case.
+///
+/// Meant for picking an emoji without the interactive picker, e.g. from a
+/// script. On failure, the error carries the closest names as suggestions.
+pub fn lookup(query: &str) -> Result<&'static Emoji, UnknownEmoji> {
+    let query = query.trim();
+    if let Some((emoji, len)) = detect_prefix(query) {
+        if len == query.len() {
+            return Ok(emoji);
+        }
+    }
+    let name = bare_name(query);
+    if let Some(emoji) = EMOJIS
+        .iter()
+        .find(|emoji| emoji.name().eq_ignore_ascii_case(&name))
+    {
+        return Ok(emoji);
+    }
+
+    Err(UnknownEmoji {
+        query: query.to_string(),
+        suggestions: suggestions(&name),
+    })
+}
+
+/// The error [`lookup`] returns for a query that names no emoji.
+#[derive(Debug, Clone)]
+pub struct UnknownEmoji {
+    query: String,
+    suggestions: Vec<&'static Emoji>,
+}
+
+impl UnknownEmoji {
+    /// The emojis whose names are closest to the query, best first. Empty
+    /// when nothing comes close.
+    pub fn suggestions(&self) -> &[&'static Emoji] {
+        &self.suggestions
+    }
+}
+
+impl fmt::Display for UnknownEmoji {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown emoji `{}`", self.query)?;
+        for (i, emoji) in self.suggestions.iter().enumerate() {
+            let sep = if i == 0 { "; did you mean" } else { "," };
+            write!(f, "{sep} {} {}", emoji.emoji(), emoji.code())?;
+        }
+        if !self.suggestions.is_empty() {
+            f.write_str("?")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for UnknownEmoji {}
+
+/// `query` lowercased, without the colons of a `:code:`.
+fn bare_name(query: &str) -> String {
+    query
+        .strip_prefix(':')
+        .and_then(|q| q.strip_suffix(':'))
+        .unwrap_or(query)
+        .to_ascii_lowercase()
+}
+
+/// Emojis whose names contain `name`, or are within a few typos of it,
+/// closest first.
+fn suggestions(name: &str) -> Vec<&'static Emoji> {
+    if name.is_empty() {
+        return Vec::new();
+    }
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut candidates: Vec<(usize, &'static Emoji)> = EMOJIS
+        .iter()
+        .filter_map(|emoji| {
+            let distance = if emoji.name().contains(name) {
+                // Closer than any typo, but behind an exact hit, which
+                // `lookup` would already have returned.
+                0
+            } else {
+                edit_distance(name, emoji.name())
+            };
+            (distance <= max_distance).then_some((distance, emoji))
+        })
+        .collect();
+    // Stable, so ties keep database order.
+    candidates.sort_by_key(|(distance, _)| *distance);
+    candidates.truncate(MAX_SUGGESTIONS);
+
+    candidates.into_iter().map(|(_, emoji)| emoji).collect()
+}
+
+/// Edit distance between `a` and `b` in chars, counting insertions,
+/// deletions, substitutions and swaps of adjacent chars (optimal string
+/// alignment), which between them cover most typos.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // Three rolling rows of the distance matrix: the one being filled and
+    // the two above it, which a swap looks back to.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut above: Vec<usize> = (0..=b.len()).collect();
+    let mut row: Vec<usize> = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        row[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (above[j] + 1).min(row[j - 1] + 1).min(above[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut above);
+        std::mem::swap(&mut above, &mut row);
+    }
+
+    above[b.len()]
+}
+
+const MAX_SUGGESTIONS: usize = 3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(query: &str) -> &'static str {
+        lookup(query).unwrap().code()
+    }
+
+    #[test]
+    fn emojis_are_found_by_glyph_code_and_name() {
+        assert_eq!(code("🐛"), ":bug:");
+        assert_eq!(code(":bug:"), ":bug:");
+        assert_eq!(code("bug"), ":bug:");
+        assert_eq!(code("Bug"), ":bug:");
+        assert_eq!(code(" sparkles "), ":sparkles:");
+    }
+
+    #[test]
+    fn glyphs_match_with_or_without_variation_selectors() {
+        assert_eq!(code("⚡"), ":zap:");
+        assert_eq!(code("⚡️"), ":zap:");
+    }
+
+    #[test]
+    fn text_after_an_emoji_is_not_a_match() {
+        assert!(lookup("🐛 fix").is_err());
+        assert!(lookup(":bug:s").is_err());
+    }
+
+    #[test]
+    fn unknown_names_suggest_close_matches() {
+        let err = lookup("bgu").unwrap_err();
+        assert_eq!(err.suggestions()[0].code(), ":bug:");
+        assert!(err
+            .to_string()
+            .starts_with("Unknown emoji `bgu`; did you mean 🐛 :bug:"));
+
+        let err = lookup(":construction_workr:").unwrap_err();
+        assert_eq!(err.suggestions()[0].code(), ":construction_worker:");
+    }
+
+    #[test]
+    fn names_containing_the_query_are_suggested() {
+        let err = lookup("key").unwrap_err();
+        let codes: Vec<_> = err.suggestions().iter().map(|e| e.code()).collect();
+        assert!(codes.contains(&":closed_lock_with_key:"), "{codes:?}");
+    }
+
+    #[test]
+    fn hopeless_queries_suggest_nothing() {
+        let err = lookup("zzzzzzzz").unwrap_err();
+        assert!(err.suggestions().is_empty());
+        assert_eq!(err.to_string(), "Unknown emoji `zzzzzzzz`");
+    }
+
+    #[test]
+    fn edit_distance_counts_single_char_edits() {
+        assert_eq!(edit_distance("bug", "bug"), 0);
+        assert_eq!(edit_distance("bgu", "bug"), 1);
+        assert_eq!(edit_distance("bu", "bug"), 1);
+        assert_eq!(edit_distance("", "bug"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("ab", "ba"), 1);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::emoji::{Emoji, EMOJIS};
+
+/// The emoji `text` starts with, given either as its glyph or its `:code:`,
+/// and the length in bytes of the prefix it was found in.
+///
+/// Variation selectors are insignificant, so `⚡` and `⚡️` both find
+/// `:zap:`; any that trail the glyph are counted in the length, so slicing
+/// the text there never leaves a stray one behind. When several emojis
+/// match, the longest prefix wins.
+///
+/// The lookup walks a trie baked into the binary at build time, so it costs
+/// a handful of byte comparisons however big the database grows.
+pub fn detect_prefix(text: &str) -> Option<(&'static Emoji, usize)> {
+    let bytes = text.as_bytes();
+    let mut node = &NODES[0];
+    let mut pos = 0;
+    let mut found = None;
+    loop {
+        if pos > 0 {
+            pos += variation_selectors_len(&bytes[pos..]);
+        }
+        if let Some(index) = node.emoji {
+            found = Some((&EMOJIS[usize::from(index)], pos));
+        }
+        let Some(byte) = bytes.get(pos) else {
+            break;
+        };
+        let Ok(edge) = node.edges.binary_search_by_key(byte, |(b, _)| *b) else {
+            break;
+        };
+        node = &NODES[usize::from(node.edges[edge].1)];
+        pos += 1;
+    }
+
+    found
+}
+
+/// Like [`detect_prefix`], but also finding the `extra` emojis, e.g. ones
+/// added at runtime from a project's config.
+///
+/// The baked trie doesn't know about those, so they're looked for with a
+/// linear scan instead; only selectors trailing their glyph are optional.
+/// When both match, the longest prefix wins, and the extra emoji on a tie:
+/// one standing in for a database emoji should be found as itself.
+pub fn detect_prefix_with<'e>(text: &str, extra: &'e [Emoji]) -> Option<(&'e Emoji, usize)> {
+    let extra_match = extra
+        .iter()
+        .filter_map(|emoji| {
+            let len = [emoji.emoji(), emoji.code()]
+                .into_iter()
+                .filter(|key| !key.is_empty() && text.starts_with(key))
+                .map(|key| key.len() + variation_selectors_len(&text.as_bytes()[key.len()..]))
+                .max()?;
+            Some((emoji, len))
+        })
+        .max_by_key(|(_, len)| *len);
+
+    match (extra_match, detect_prefix(text)) {
+        (Some(extra), Some(baked)) if baked.1 > extra.1 => Some(baked),
+        (Some(extra), _) => Some(extra),
+        (None, baked) => baked,
+    }
+}
+
+/// Length in bytes of the run of variation selectors `bytes` starts with.
+fn variation_selectors_len(bytes: &[u8]) -> usize {
+    bytes
+        .chunks(VARIATION_SELECTOR_LEN)
+        .take_while(|chunk| VARIATION_SELECTORS.contains(chunk))
+        .count()
+        * VARIATION_SELECTOR_LEN
+}
+
+/// A trie node: its outgoing edges sorted by byte, and the index into
+/// [`EMOJIS`] of the emoji whose glyph or code ends here, if any.
+struct Node {
+    edges: &'static [(u8, u16)],
+    emoji: Option<u16>,
+}
+
+/// UTF-8 encoding of the text (U+FE0E) and emoji (U+FE0F) presentation
+/// selectors. The trie's keys were built with these stripped.
+const VARIATION_SELECTORS: [&[u8]; 2] = [b"\xef\xb8\x8e", b"\xef\xb8\x8f"];
+const VARIATION_SELECTOR_LEN: usize = 3;
+
+include!(concat!(env!("OUT_DIR"), "/prefix_trie.rs"));
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_prefix, detect_prefix_with};
+    use crate::emoji::Emoji;
+
+    fn code_and_len(text: &str) -> Option<(&'static str, usize)> {
+        detect_prefix(text).map(|(emoji, len)| (emoji.code(), len))
+    }
+
+    #[test]
+    fn glyph_prefix_is_detected() {
+        assert_eq!(code_and_len("🐛 Fix it"), Some((":bug:", "🐛".len())));
+    }
+
+    #[test]
+    fn code_prefix_is_detected() {
+        assert_eq!(code_and_len(":bug: Fix it"), Some((":bug:", 5)));
+    }
+
+    #[test]
+    fn variation_selectors_are_optional_and_counted() {
+        // The database has `⚡️` with the selector, `✨` without.
+        assert_eq!(code_and_len("⚡ Faster"), Some((":zap:", "⚡".len())));
+        assert_eq!(code_and_len("⚡️ Faster"), Some((":zap:", "⚡️".len())));
+        assert_eq!(code_and_len("✨️ New"), Some((":sparkles:", "✨️".len())));
+    }
+
+    #[test]
+    fn zwj_sequences_are_detected_whole() {
+        assert_eq!(
+            code_and_len("🧑‍💻 Nicer dev loop"),
+            Some((":technologist:", "🧑‍💻".len()))
+        );
+    }
+
+    #[test]
+    fn only_the_start_of_the_text_counts() {
+        assert_eq!(code_and_len("Revert \"🐛 Fix it\""), None);
+        assert_eq!(code_and_len(" 🐛 Fix it"), None);
+        assert_eq!(code_and_len("Fix :bug:"), None);
+    }
+
+    #[test]
+    fn partial_codes_do_not_match() {
+        assert_eq!(code_and_len(":bu"), None);
+        assert_eq!(code_and_len(":construction"), None);
+        assert_eq!(code_and_len(""), None);
+    }
+
+    #[test]
+    fn extra_emojis_are_detected_too() {
+        let extra = [
+            Emoji::new("🦀", ":crab:", "Rust-specific change."),
+            Emoji::new("🐛", ":insect:", "Our own bug."),
+        ];
+        let detect = |text| detect_prefix_with(text, &extra).map(|(e, len)| (e.code(), len));
+
+        assert_eq!(detect("🦀 Port it"), Some((":crab:", "🦀".len())));
+        assert_eq!(detect(":crab: Port it"), Some((":crab:", 6)));
+        assert_eq!(
+            detect("🦀\u{fe0f} Port it"),
+            Some((":crab:", "🦀\u{fe0f}".len()))
+        );
+        // The database still counts, and the extra one wins a tie.
+        assert_eq!(detect("✨ New"), Some((":sparkles:", "✨".len())));
+        assert_eq!(detect("🐛 Fix"), Some((":insect:", "🐛".len())));
+        assert_eq!(detect(":bug: Fix"), Some((":bug:", 5)));
+        assert_eq!(detect("Port it"), None);
+    }
+
+    #[test]
+    fn every_emoji_is_found_by_glyph_and_code() {
+        for emoji in crate::emoji::EMOJIS {
+            let (found, len) = detect_prefix(emoji.emoji()).unwrap();
+            assert_eq!((found.code(), len), (emoji.code(), emoji.emoji().len()));
+            let (found, len) = detect_prefix(emoji.code()).unwrap();
+            assert_eq!((found.code(), len), (emoji.code(), emoji.code().len()));
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph, Widget},
+};
+
+use crate::{colors::Colors, emoji::Emoji, template::Template};
+
+/// A read-only look at the commit being prepared: its subject with the
+/// emojis about to be picked in front, and the files it changes.
+///
+/// The emojis are written into the buffer as they are, so frontends that
+/// paint glyphs in an overlay of their own are better off without one.
+#[derive(Debug, Clone)]
+pub struct Preview {
+    subject: String,
+    files: Vec<String>,
+    template: Template,
+}
+
+impl Preview {
+    /// A preview of a commit with `subject` so far, changing `files`, each
+    /// summed up in a line like `M src/main.rs`.
+    pub fn new(subject: impl Into<String>, files: Vec<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            files,
+            template: Template::default(),
+        }
+    }
+
+    /// Write the emojis in front of the subject through `template`, the way
+    /// the commit message will have them.
+    pub fn with_template(mut self, template: Template) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Rows the preview takes up, borders included.
+    pub fn height(&self) -> u16 {
+        let files = match self.files.len() {
+            0 => 0,
+            n if n > MAX_FILES => MAX_FILES + 2,
+            n => n + 1,
+        };
+
+        (3 + files) as u16
+    }
+
+    /// Render the preview into `area`, with `emojis` in front of the
+    /// subject.
+    pub fn render(&self, emojis: &[Emoji], colors: &Colors, area: Rect, buf: &mut Buffer) {
+        let base = Style::default().fg(colors.unselected);
+        let dim = base.add_modifier(Modifier::DIM);
+        let prefix = match emojis {
+            [] => String::new(),
+            emojis => self.template.render_many(emojis, None),
+        };
+        let mut lines = vec![match self.subject.trim() {
+            "" => Line::from(vec![
+                Span::styled(prefix, Style::default().fg(colors.selected)),
+                Span::styled(NO_SUBJECT, dim),
+            ]),
+            subject => Line::from(vec![
+                Span::styled(prefix, Style::default().fg(colors.selected)),
+                Span::styled(subject, base),
+            ]),
+        }];
+        if !self.files.is_empty() {
+            lines.push(Line::default());
+            for file in self.files.iter().take(MAX_FILES) {
+                lines.push(Line::styled(file.as_str(), dim));
+            }
+            if self.files.len() > MAX_FILES {
+                let more = self.files.len() - MAX_FILES;
+                lines.push(Line::styled(format!("… and {more} more"), dim));
+            }
+        }
+
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(TITLE)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(colors.border))
+                    .padding(Padding::horizontal(1)),
+            )
+            .render(area, buf);
+    }
+}
+
+const TITLE: &str = "Preview";
+const NO_SUBJECT: &str = "(no subject yet)";
+/// Files listed before the rest are summed up in a line.
+const MAX_FILES: usize = 5;
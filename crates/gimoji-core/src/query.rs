@@ -0,0 +1,284 @@
+use std::ops::Range;
+
+use crate::{
+    emoji::Emoji,
+    search::{find_ignore_ascii_case, starts_with_ignore_ascii_case},
+};
+
+/// A search as typed into the picker: plain words to rank the emojis by,
+/// and filters that narrow them down.
+///
+/// Plain words match loosely, the way [`rank`] describes. Filters match
+/// strictly, ignoring ASCII case:
+///
+/// * `"a phrase"` in quotes: the code, name or description contains it;
+/// * `code:`, `name:` or `desc:` and a word, or a quoted phrase, as in
+///   `desc:"new features"`: that field contains it;
+/// * `cat:` and a category, or the start of one, as in `cat:deps`;
+/// * `semver:` and `major`, `minor`, `patch` or `none`, or the start of one;
+/// * `-` in front of any of the above, or of a plain word, as in `-revert`,
+///   leaves out the emojis it would match instead.
+///
+/// An emoji has one category and semver bump, so `cat:` and `semver:`
+/// filters match emojis with any of theirs; the others all have to match.
+///
+/// [`rank`]: crate::search::rank
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    words: Vec<String>,
+    filters: Vec<Filter>,
+}
+
+impl Query {
+    /// Parse `query`, which never fails: whatever isn't a filter is a plain
+    /// word, and an unclosed quote runs to the end.
+    pub fn parse(query: &str) -> Self {
+        let query = query.to_ascii_lowercase();
+        let mut parsed = Self::default();
+        let mut rest = query.trim_start();
+        while !rest.is_empty() {
+            let negated = rest.len() > 1
+                && rest.starts_with('-')
+                && !rest[1..].starts_with(char::is_whitespace);
+            let term = if negated { &rest[1..] } else { rest };
+            let (field, term) = match term.split_once(':') {
+                Some((name, value)) => match Field::named(name) {
+                    Some(field) => (field, value),
+                    None => (Field::Any, term),
+                },
+                None => (Field::Any, term),
+            };
+            let (value, quoted, after) = match term.strip_prefix('"') {
+                Some(phrase) => match phrase.split_once('"') {
+                    Some((value, after)) => (value, true, after),
+                    None => (phrase, true, ""),
+                },
+                None => match term.split_once(char::is_whitespace) {
+                    Some((value, after)) => (value, false, after),
+                    None => (term, false, ""),
+                },
+            };
+            rest = after.trim_start();
+
+            if field == Field::Any && !negated && !quoted {
+                parsed.words.push(value.to_string());
+            } else if !value.is_empty() {
+                parsed.filters.push(Filter {
+                    field,
+                    value: value.to_string(),
+                    negated,
+                });
+            }
+        }
+
+        parsed
+    }
+
+    /// The plain words, lowercased and separated by single spaces.
+    pub fn text(&self) -> String {
+        self.words.join(" ")
+    }
+
+    /// Whether `emoji` passes all the filters.
+    pub fn matches(&self, emoji: &Emoji) -> bool {
+        let hit = |filter: &&Filter| filter.field.find(emoji, &filter.value).is_some();
+        let all = self
+            .filters
+            .iter()
+            .filter(|filter| filter.negated || !filter.field.is_single())
+            .all(|filter| hit(&filter) != filter.negated);
+        let any = [Field::Category, Field::Semver].into_iter().all(|field| {
+            let mut wanted = self
+                .filters
+                .iter()
+                .filter(|filter| filter.field == field && !filter.negated)
+                .peekable();
+            wanted.peek().is_none() || wanted.any(|filter| hit(&filter))
+        });
+
+        all && any
+    }
+
+    /// Byte ranges of `emoji`'s code and of its description the filters
+    /// matched, to highlight along with those of the plain words.
+    pub(crate) fn highlights(&self, emoji: &Emoji) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+        let mut code = Vec::new();
+        let mut description = Vec::new();
+        for filter in self.filters.iter().filter(|filter| !filter.negated) {
+            match filter.field.find(emoji, &filter.value) {
+                Some(Found::Code(range)) => code.push(range),
+                Some(Found::Description(range)) => description.push(range),
+                Some(Found::Elsewhere) | None => (),
+            }
+        }
+
+        (code, description)
+    }
+}
+
+/// A word of a [`Query`] that filters the emojis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Filter {
+    field: Field,
+    /// Lowercased, and never empty.
+    value: String,
+    negated: bool,
+}
+
+/// What part of an emoji a [`Filter`] looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// The code, name or description.
+    Any,
+    Code,
+    Name,
+    Description,
+    Category,
+    Semver,
+}
+
+impl Field {
+    /// The field `name:` in a query stands for, if any.
+    fn named(name: &str) -> Option<Self> {
+        Some(match name {
+            "code" => Self::Code,
+            "name" => Self::Name,
+            "desc" => Self::Description,
+            "cat" => Self::Category,
+            "semver" => Self::Semver,
+            _ => return None,
+        })
+    }
+
+    /// Whether an emoji has just the one of the field, so that filters on
+    /// it match emojis with any of theirs.
+    fn is_single(self) -> bool {
+        matches!(self, Self::Category | Self::Semver)
+    }
+
+    /// Where `emoji`'s field has `value` (contains it, or for categories
+    /// and semver bumps, starts with it), if it does.
+    fn find(self, emoji: &Emoji, value: &str) -> Option<Found> {
+        let code = emoji.code();
+        match self {
+            Self::Any => Self::Code
+                .find(emoji, value)
+                .or_else(|| Self::Name.find(emoji, value))
+                .or_else(|| Self::Description.find(emoji, value)),
+            Self::Code => {
+                let at = find_ignore_ascii_case(code, value)?;
+                Some(Found::Code(at..at + value.len()))
+            }
+            Self::Name => {
+                let at = find_ignore_ascii_case(emoji.name(), value)?;
+                // Shown where the name sits in the code, as the name column
+                // isn't rendered.
+                Some(match code.find(emoji.name()) {
+                    Some(name_at) => Found::Code(name_at + at..name_at + at + value.len()),
+                    None => Found::Elsewhere,
+                })
+            }
+            Self::Description => {
+                let at = find_ignore_ascii_case(emoji.description(), value)?;
+                Some(Found::Description(at..at + value.len()))
+            }
+            Self::Category => {
+                let category = emoji.category()?;
+                starts_with_ignore_ascii_case(category, value).then_some(Found::Elsewhere)
+            }
+            Self::Semver => {
+                let bump = emoji.semver().map_or(NO_SEMVER, |bump| bump.as_str());
+                bump.starts_with(value).then_some(Found::Elsewhere)
+            }
+        }
+    }
+}
+
+/// Where a [`Filter`] matched.
+enum Found {
+    Code(Range<usize>),
+    Description(Range<usize>),
+    /// In a field that isn't highlighted.
+    Elsewhere,
+}
+
+/// What `semver:` filters take for emojis that don't bump the version.
+const NO_SEMVER: &str = "none";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emoji::EMOJIS;
+
+    fn filter(field: Field, value: &str, negated: bool) -> Filter {
+        Filter {
+            field,
+            value: value.to_string(),
+            negated,
+        }
+    }
+
+    fn codes(query: &str) -> Vec<&'static str> {
+        let query = Query::parse(query);
+        EMOJIS
+            .iter()
+            .filter(|emoji| query.matches(emoji))
+            .map(|emoji| emoji.code())
+            .collect()
+    }
+
+    #[test]
+    fn queries_split_into_words_and_filters() {
+        let query = Query::parse(r#"  Fix code:BUG -revert "a bug" -desc:"new features" cat:  "#);
+        assert_eq!(query.text(), "fix");
+        assert_eq!(
+            query.filters,
+            [
+                filter(Field::Code, "bug", false),
+                filter(Field::Any, "revert", true),
+                filter(Field::Any, "a bug", false),
+                filter(Field::Description, "new features", true),
+            ]
+        );
+
+        // Neither of these are filters.
+        let query = Query::parse(":bug: - http://example.com");
+        assert_eq!(query.text(), ":bug: - http://example.com");
+        assert!(query.filters.is_empty());
+        // Nor is an unclosed quote left out.
+        assert_eq!(
+            Query::parse(r#"desc:"new fea"#).filters,
+            [filter(Field::Description, "new fea", false)]
+        );
+    }
+
+    #[test]
+    fn fields_narrow_down_the_emojis() {
+        assert_eq!(codes("code:arrow_"), [":arrow_down:", ":arrow_up:"]);
+        assert_eq!(codes("name:rewind"), [":rewind:"]);
+        assert_eq!(codes(r#"desc:"a bug""#), [":bug:"]);
+        assert_eq!(codes(r#""critical hotfix""#), [":ambulance:"]);
+        assert_eq!(codes("semver:major"), [":boom:"]);
+        assert_eq!(codes("semver:min"), [":sparkles:"]);
+        assert!(codes("semver:none").contains(&":memo:"));
+        // The entity isn't searched: every one starts with `&#x1f`.
+        assert!(codes(r#""1f""#).is_empty());
+    }
+
+    #[test]
+    fn negation_leaves_emojis_out() {
+        let tests = codes("cat:testing");
+        assert_eq!(codes("cat:testing -mock").len(), tests.len() - 1);
+        assert!(!codes("cat:fixes -revert").contains(&":rewind:"));
+        assert!(!codes("-semver:none").contains(&":memo:"));
+        assert!(codes("-cat:fixes cat:fixes").is_empty());
+    }
+
+    #[test]
+    fn single_valued_fields_match_any_of_theirs() {
+        let both = codes("semver:major semver:minor");
+        assert_eq!(both, [":sparkles:", ":boom:"]);
+        // Other fields have to match all.
+        assert!(codes("code:bug code:zap").is_empty());
+    }
+}
@@ -0,0 +1,504 @@
+use std::{cmp::Reverse, ops::Range};
+
+use crate::{emoji::Emoji, lookup::edit_distance, query::Query};
+
+/// The emojis matching `query`, best match first. An empty query matches
+/// everything, in the original order.
+///
+/// Matching is case-insensitive and forgiving, in descending order of rank:
+///
+/// * the query *is* the emoji's name, `:code:` or glyph;
+/// * the name or code starts with it;
+/// * the code contains it;
+/// * the description contains it, at the start of a word ahead of
+///   elsewhere;
+/// * its letters appear in order across the name and description, starting
+///   at a word (so `bgfx` finds `:bug:`, "Fix a bug");
+/// * each of its words is a typo or two away from a word of theirs.
+///
+/// Emojis of equal rank keep their original order.
+///
+/// That's for the plain words of the query; its filters, like `cat:deps`
+/// or `-revert`, keep to the emojis they match. See [`Query`].
+pub fn rank<'e>(emojis: impl IntoIterator<Item = &'e Emoji>, query: &str) -> Vec<Match<'e>> {
+    let query = Query::parse(query);
+    let needle = query.text();
+    let mut ranked: Vec<Match<'e>> = emojis
+        .into_iter()
+        .filter(|emoji| query.matches(emoji))
+        .filter_map(|emoji| {
+            let mut found = find_match(emoji, &needle)?;
+            let (code, description) = query.highlights(emoji);
+            found.code = merged(found.code, code);
+            found.description = merged(found.description, description);
+            Some(found)
+        })
+        .collect();
+    // Stable, so equal scores keep the original order.
+    ranked.sort_by_key(|m| Reverse(m.score));
+
+    ranked
+}
+
+/// An emoji matching a search, and which parts of it matched.
+#[derive(Debug, Clone)]
+pub struct Match<'e> {
+    emoji: &'e Emoji,
+    score: u32,
+    code: Vec<Range<usize>>,
+    description: Vec<Range<usize>>,
+}
+
+impl<'e> Match<'e> {
+    pub fn emoji(&self) -> &'e Emoji {
+        self.emoji
+    }
+
+    /// Byte ranges of the emoji's code that matched, in order and
+    /// non-overlapping.
+    pub fn code_ranges(&self) -> &[Range<usize>] {
+        &self.code
+    }
+
+    /// Byte ranges of the emoji's description that matched, in order and
+    /// non-overlapping.
+    pub fn description_ranges(&self) -> &[Range<usize>] {
+        &self.description
+    }
+}
+
+/// How well `emoji` matches `needle`, or `None` if it doesn't match at all.
+/// `needle` must be trimmed and lowercased.
+fn find_match<'e>(emoji: &'e Emoji, needle: &str) -> Option<Match<'e>> {
+    let hit = |score, code, description| {
+        Some(Match {
+            emoji,
+            score,
+            code,
+            description,
+        })
+    };
+    if needle.is_empty() {
+        return hit(0, vec![], vec![]);
+    }
+    if !needle.is_ascii() {
+        // A glyph, or a word of a project's own emoji, e.g. "Übersetzung".
+        if emoji.emoji().contains(needle) {
+            return hit(EXACT, vec![], vec![]);
+        }
+        if let Some(range) = find_ignore_case(emoji.code(), needle) {
+            return hit(IN_CODE, vec![range], vec![]);
+        }
+        if find_ignore_case(emoji.name(), needle).is_some() {
+            return hit(IN_CODE, vec![], vec![]);
+        }
+        let range = find_ignore_case(emoji.description(), needle)?;
+        return hit(IN_DESCRIPTION, vec![], vec![range]);
+    }
+
+    let name = emoji.name();
+    let code = emoji.code();
+    let description = emoji.description();
+    // Where the name sits in the code, so hits on the name can be shown
+    // there; the name column isn't rendered.
+    let name_at = code.find(name);
+    let in_name = |range: Range<usize>| match name_at {
+        Some(at) => single(at + range.start, range.len()),
+        None => vec![],
+    };
+    if code.eq_ignore_ascii_case(needle) {
+        return hit(EXACT, single(0, code.len()), vec![]);
+    }
+    if name.eq_ignore_ascii_case(needle) {
+        return hit(EXACT, in_name(0..name.len()), vec![]);
+    }
+    if starts_with_ignore_ascii_case(code, needle) {
+        return hit(PREFIX, single(0, needle.len()), vec![]);
+    }
+    if starts_with_ignore_ascii_case(name, needle) {
+        return hit(PREFIX, in_name(0..needle.len()), vec![]);
+    }
+    if let Some(at) = find_ignore_ascii_case(code, needle) {
+        return hit(IN_CODE, single(at, needle.len()), vec![]);
+    }
+    if let Some(at) = find_ignore_ascii_case(description, needle) {
+        let at_word_start = at == 0 || !description.as_bytes()[at - 1].is_ascii_alphanumeric();
+        let score = if at_word_start {
+            IN_DESCRIPTION_WORD
+        } else {
+            IN_DESCRIPTION
+        };
+        return hit(score, vec![], single(at, needle.len()));
+    }
+    if let Some((score, positions)) = subsequence(name, description, needle) {
+        let mut code_ranges = Vec::new();
+        let mut description_ranges = Vec::new();
+        for pos in positions {
+            // The haystack is the name, a space, then the description.
+            if pos < name.len() {
+                if let Some(at) = name_at {
+                    push_byte(&mut code_ranges, at + pos);
+                }
+            } else {
+                push_byte(&mut description_ranges, pos - name.len() - 1);
+            }
+        }
+        let score = SUBSEQUENCE + score.min(SUBSEQUENCE_MAX_BONUS);
+        return hit(score, code_ranges, description_ranges);
+    }
+    if let Some((name_words, description_words)) = typo(name, description, needle) {
+        let code_ranges = name_words.into_iter().flat_map(in_name).collect();
+        return hit(TYPO, code_ranges, description_words);
+    }
+
+    None
+}
+
+/// Score `needle`'s letters appearing in order across `name` and then
+/// `description`, the first of them at the start of a word, and where they
+/// were found in `name`, a space and `description` run together. Runs of
+/// consecutive letters and letters starting words score extra, since that's
+/// what abbreviations are made of.
+fn subsequence(name: &str, description: &str, needle: &str) -> Option<(u32, Vec<usize>)> {
+    let mut needle = needle
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace())
+        .peekable();
+    let haystack = name.bytes().chain([b' ']).chain(description.bytes());
+    let mut score = 0;
+    let mut positions = Vec::new();
+    let mut previous = b' ';
+    let mut previous_matched = false;
+    for (pos, byte) in haystack.enumerate() {
+        let Some(wanted) = needle.peek() else {
+            break;
+        };
+        let word_start = !previous.is_ascii_alphanumeric();
+        let first = positions.is_empty();
+        previous = byte;
+        if !byte.eq_ignore_ascii_case(wanted) || (first && !word_start) {
+            previous_matched = false;
+            continue;
+        }
+        score += 1;
+        if word_start {
+            score += 3;
+        }
+        if previous_matched {
+            score += 2;
+        }
+        previous_matched = true;
+        positions.push(pos);
+        needle.next();
+    }
+
+    needle.peek().is_none().then_some((score, positions))
+}
+
+/// Whether every word of `needle` is within a typo or two of a word in
+/// `name` or `description`, or of the start of one, and if so, the byte
+/// ranges of the words it matched in each. Words under three letters are too
+/// short to tell a typo from a different word, so those have to match the
+/// start of a word exactly.
+fn typo(name: &str, description: &str, needle: &str) -> Option<(Ranges, Ranges)> {
+    let mut name_words = Vec::new();
+    let mut description_words = Vec::new();
+    for (_, typed) in words(needle) {
+        let tolerance = match typed.len() {
+            0..=2 => 0,
+            3..=6 => 1,
+            _ => 2,
+        };
+        let close = |word: &str| {
+            let word = word.to_ascii_lowercase();
+            // Also compare against the word cut down to about the typed
+            // length, so a misspelt start of a long word counts.
+            let stem = &word[..word.len().min(typed.len() + 1)];
+            edit_distance(typed, &word) <= tolerance || edit_distance(typed, stem) <= tolerance
+        };
+        if let Some((at, word)) = words(name).find(|(_, word)| close(word)) {
+            name_words.push(at..at + word.len());
+        } else if let Some((at, word)) = words(description).find(|(_, word)| close(word)) {
+            description_words.push(at..at + word.len());
+        } else {
+            return None;
+        }
+    }
+    name_words.sort_by_key(|range| range.start);
+    name_words.dedup();
+    description_words.sort_by_key(|range| range.start);
+    description_words.dedup();
+
+    Some((name_words, description_words))
+}
+
+type Ranges = Vec<Range<usize>>;
+
+/// The one byte range of `len` bytes from `start`.
+fn single(start: usize, len: usize) -> Ranges {
+    let range = start..start + len;
+    vec![range]
+}
+
+/// The ASCII-alphanumeric words of `text`, with their byte offsets.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        let start = pos + bytes[pos..].iter().position(u8::is_ascii_alphanumeric)?;
+        let len = bytes[start..]
+            .iter()
+            .position(|b| !b.is_ascii_alphanumeric())
+            .unwrap_or(bytes.len() - start);
+        pos = start + len;
+
+        Some((start, &text[start..pos]))
+    })
+}
+
+/// `ranges` and `more` together, in order, with overlapping ranges joined
+/// up.
+fn merged(mut ranges: Ranges, more: Ranges) -> Ranges {
+    if more.is_empty() {
+        return ranges;
+    }
+    ranges.extend(more);
+    ranges.sort_by_key(|range| range.start);
+    let mut joined: Ranges = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match joined.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => joined.push(range),
+        }
+    }
+
+    joined
+}
+
+/// Add the byte at `pos` to `ranges`, growing the last range if it ends
+/// right there.
+fn push_byte(ranges: &mut Vec<Range<usize>>, pos: usize) {
+    match ranges.last_mut() {
+        Some(last) if last.end == pos => last.end += 1,
+        _ => ranges.push(pos..pos + 1),
+    }
+}
+
+pub(crate) fn starts_with_ignore_ascii_case(haystack: &str, needle_lower: &str) -> bool {
+    haystack.len() >= needle_lower.len()
+        && haystack.as_bytes()[..needle_lower.len()].eq_ignore_ascii_case(needle_lower.as_bytes())
+}
+
+/// Byte offset of the first case-insensitive occurrence of `needle_lower`
+/// in `haystack`.
+pub(crate) fn find_ignore_ascii_case(haystack: &str, needle_lower: &str) -> Option<usize> {
+    if needle_lower.is_empty() {
+        return Some(0);
+    }
+    if needle_lower.len() > haystack.len() {
+        return None;
+    }
+    haystack
+        .as_bytes()
+        .windows(needle_lower.len())
+        .position(|w| w.eq_ignore_ascii_case(needle_lower.as_bytes()))
+}
+
+/// Byte range of the first occurrence of `needle` in `haystack`, ignoring
+/// case beyond ASCII too, where [`find_ignore_ascii_case`] would do.
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<Range<usize>> {
+    let needle: Vec<char> = needle.chars().flat_map(char::to_lowercase).collect();
+    haystack.char_indices().find_map(|(start, _)| {
+        let mut wanted = needle.iter();
+        for (at, c) in haystack[start..].char_indices() {
+            if !c.to_lowercase().all(|lower| wanted.next() == Some(&lower)) {
+                return None;
+            }
+            if wanted.as_slice().is_empty() {
+                return Some(start..start + at + c.len_utf8());
+            }
+        }
+
+        None
+    })
+}
+
+// Score bands, one per rank `rank` documents. Bands are far enough apart
+// that the bonuses within one never reach the next.
+const EXACT: u32 = 1000;
+const PREFIX: u32 = 900;
+const IN_CODE: u32 = 800;
+const IN_DESCRIPTION_WORD: u32 = 710;
+const IN_DESCRIPTION: u32 = 700;
+const SUBSEQUENCE: u32 = 300;
+const SUBSEQUENCE_MAX_BONUS: u32 = 299;
+const TYPO: u32 = 100;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emoji::EMOJIS;
+
+    #[test]
+    fn ascii_case_insensitive_find() {
+        assert_eq!(find_ignore_ascii_case("Sparkles", "spark"), Some(0));
+        assert_eq!(find_ignore_ascii_case("Sparkles", "KLE"), Some(4));
+        assert_eq!(find_ignore_ascii_case("Sparkles", "fire"), None);
+        assert_eq!(find_ignore_ascii_case("anything", ""), Some(0));
+        assert_eq!(find_ignore_ascii_case("hi", "hello"), None);
+    }
+
+    fn codes(query: &str) -> Vec<&'static str> {
+        rank(EMOJIS, query)
+            .iter()
+            .map(|m| m.emoji().code())
+            .collect()
+    }
+
+    /// The matched parts of the best match's code and description.
+    fn best_ranges(query: &str) -> (Vec<&'static str>, Vec<&'static str>) {
+        let ranked = rank(EMOJIS, query);
+        let best = &ranked[0];
+        let emoji = best.emoji();
+        (
+            best.code_ranges()
+                .iter()
+                .map(|r| &emoji.code()[r.clone()])
+                .collect(),
+            best.description_ranges()
+                .iter()
+                .map(|r| &emoji.description()[r.clone()])
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn empty_query_keeps_everything_in_order() {
+        let all: Vec<_> = EMOJIS.iter().map(|e| e.code()).collect();
+        assert_eq!(codes(""), all);
+        assert_eq!(codes("  "), all);
+    }
+
+    #[test]
+    fn exact_names_and_codes_rank_first() {
+        assert_eq!(codes("bug")[0], ":bug:");
+        assert_eq!(codes(":bug:")[0], ":bug:");
+        assert_eq!(codes("ART")[0], ":art:");
+    }
+
+    #[test]
+    fn name_hits_outrank_description_hits() {
+        // `:test_tube:`'s name starts with "test", while plenty of
+        // descriptions ahead of it in the database mention tests.
+        assert_eq!(codes("test")[0], ":test_tube:");
+    }
+
+    #[test]
+    fn description_hits_at_word_starts_rank_higher() {
+        let bug = EMOJIS.iter().find(|e| e.code() == ":bug:").unwrap();
+        assert_eq!(find_match(bug, "fix").unwrap().score, IN_DESCRIPTION_WORD);
+        assert_eq!(find_match(bug, "ix").unwrap().score, IN_DESCRIPTION);
+    }
+
+    #[test]
+    fn abbreviations_match_as_subsequences() {
+        assert_eq!(codes("bgfx")[0], ":bug:");
+        assert!(codes("cnstwrk").contains(&":construction_worker:"));
+    }
+
+    #[test]
+    fn subsequences_must_start_at_a_word() {
+        assert!(!codes("ugf").contains(&":bug:"));
+    }
+
+    #[test]
+    fn typos_are_tolerated() {
+        assert!(codes("bgu").contains(&":bug:"));
+        assert!(codes("perfromance").contains(&":zap:"));
+        assert!(codes("documnetation").contains(&":memo:"));
+    }
+
+    #[test]
+    fn glyphs_match_their_emoji() {
+        assert_eq!(codes("🐛"), [":bug:"]);
+    }
+
+    #[test]
+    fn words_beyond_ascii_match_ignoring_case() {
+        let emojis = [
+            Emoji::new("🌐", ":i18n:", "Übersetzungen aktualisieren."),
+            Emoji::new("🐛", ":bug:", "Fehler beheben."),
+        ];
+        let found = rank(&emojis, "übersetzung");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].emoji().code(), ":i18n:");
+        let description = found[0].emoji().description();
+        let ranges = found[0].description_ranges();
+        assert_eq!(&description[ranges[0].clone()], "Übersetzung");
+        assert!(rank(&emojis, "grüße").is_empty());
+    }
+
+    #[test]
+    fn nonsense_matches_nothing() {
+        assert!(codes("zzzzzzzz_no_match_zzzzzz").is_empty());
+    }
+
+    #[test]
+    fn categories_narrow_the_search() {
+        let deps = [
+            ":arrow_down:",
+            ":arrow_up:",
+            ":pushpin:",
+            ":heavy_plus_sign:",
+            ":heavy_minus_sign:",
+        ];
+        assert_eq!(codes("cat:deps"), deps);
+        assert_eq!(codes("CAT:dep"), deps);
+        assert_eq!(codes("cat:deps up"), [":arrow_up:"]);
+        assert_eq!(codes("up cat:deps")[0], ":arrow_up:");
+        assert_eq!(codes("cat:docs cat:deps").len(), 4 + deps.len());
+        assert!(codes("cat:nope").is_empty());
+    }
+
+    #[test]
+    fn filters_narrow_the_ranking_down() {
+        assert_eq!(codes("fix")[0], ":bug:");
+        assert!(!codes("fix -cat:fixes").contains(&":bug:"));
+        assert_eq!(codes("fix code:bandage"), [":adhesive_bandage:"]);
+        assert!(codes("perf semver:patch").starts_with(&[":zap:"]));
+    }
+
+    #[test]
+    fn filter_hits_are_reported_with_the_words() {
+        assert_eq!(best_ranges("desc:bug"), (vec![], vec!["bug"]));
+        assert_eq!(best_ranges("bug desc:fix"), (vec!["bug"], vec!["Fix"]));
+        assert_eq!(
+            best_ranges(r#"spark desc:"new features""#),
+            (vec!["spark"], vec!["new features"])
+        );
+        assert_eq!(best_ranges("name:bug"), (vec!["bug"], vec![]));
+    }
+
+    #[test]
+    fn matched_parts_are_reported() {
+        assert_eq!(best_ranges(":bug:"), (vec![":bug:"], vec![]));
+        assert_eq!(best_ranges("bug"), (vec!["bug"], vec![]));
+        assert_eq!(best_ranges("spark"), (vec!["spark"], vec![]));
+        assert_eq!(best_ranges("bgfx"), (vec!["b", "g"], vec!["F", "x"]));
+        assert_eq!(best_ranges("perfromance"), (vec![], vec!["performance"]));
+    }
+
+    #[test]
+    fn unmatched_parts_are_not_reported() {
+        assert_eq!(best_ranges(""), (vec![], vec![]));
+        assert_eq!(best_ranges("🐛"), (vec![], vec![]));
+    }
+
+    #[test]
+    fn words_come_with_their_offsets() {
+        let found: Vec<_> = words("Fix a bug.").collect();
+        assert_eq!(found, [(0, "Fix"), (4, "a"), (6, "bug")]);
+        assert_eq!(words("").count(), 0);
+        assert_eq!(words("...").count(), 0);
+    }
+}
@@ -8,13 +8,23 @@ use ratatui::{
 pub struct SearchEntry<'c> {
     text: String,
     colors: &'c Colors,
+    title: &'static str,
+    placeholder: &'static str,
 }
 
 impl<'c> SearchEntry<'c> {
     pub fn new(colors: &'c Colors) -> Self {
+        Self::labelled(colors, TITLE, DEFAULT_TEXT)
+    }
+
+    /// A text field titled `title`, showing `placeholder` while empty, for
+    /// input other than the search.
+    pub fn labelled(colors: &'c Colors, title: &'static str, placeholder: &'static str) -> Self {
         Self {
             text: String::from(""),
             colors,
+            title,
+            placeholder,
         }
     }
 
@@ -57,13 +67,13 @@ impl Widget for &SearchEntry<'_> {
         // invisible on a light page background.
         let base = Style::default().fg(self.colors.unselected);
         let (text, style) = if self.text.is_empty() {
-            (DEFAULT_TEXT, base.add_modifier(Modifier::DIM))
+            (self.placeholder, base.add_modifier(Modifier::DIM))
         } else {
             (&*self.text, base)
         };
         let paragraph = Paragraph::new(Span::styled(text, style)).block(
             Block::default()
-                .title(TITLE)
+                .title(self.title)
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(self.colors.border))
                 .padding(Padding {
@@ -1,14 +1,19 @@
+use std::{borrow::Cow, ops::Range};
+
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Rect},
     style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{
-        Block, Borders, HighlightSpacing, Padding, Row, StatefulWidget, Table, TableState, Widget,
+        Block, Borders, Cell, HighlightSpacing, Padding, Row, StatefulWidget, Table, TableState,
+        Widget,
     },
 };
 
 use crate::colors::Colors;
-use crate::emoji::Emoji;
+use crate::emoji::{Emoji, CATEGORIES};
+use crate::search::{self, Match};
 
 /// Where the picker's emoji glyph for each row comes from.
 #[derive(Debug, Clone, Copy)]
@@ -23,14 +28,19 @@ pub enum EmojiSource {
 }
 
 pub struct SelectionView<'c> {
-    emojis: &'static [Emoji],
+    emojis: Cow<'static, [Emoji]>,
     state: TableState,
     colors: &'c Colors,
     source: EmojiSource,
+    show_semver: bool,
+    grouped: bool,
+    suggested: Vec<String>,
+    recent: Vec<String>,
+    toggled: Vec<Emoji>,
 }
 
 impl<'c> SelectionView<'c> {
-    pub fn new(emojis: &'static [Emoji], colors: &'c Colors, source: EmojiSource) -> Self {
+    pub fn new(emojis: Cow<'static, [Emoji]>, colors: &'c Colors, source: EmojiSource) -> Self {
         let mut state = TableState::default();
         state.select(Some(0));
 
@@ -39,6 +49,11 @@ impl<'c> SelectionView<'c> {
             state,
             colors,
             source,
+            show_semver: false,
+            grouped: false,
+            suggested: Vec::new(),
+            recent: Vec::new(),
+            toggled: Vec::new(),
         }
     }
 
@@ -46,56 +61,164 @@ impl<'c> SelectionView<'c> {
         self.colors = colors;
     }
 
-    pub fn filtered_view(&mut self, search_text: &str) -> FilteredView<'_, '_> {
-        let needle = search_text.to_ascii_lowercase();
-        let emojis: Vec<&Emoji> = self
-            .emojis
-            .iter()
-            .filter(|emoji| needle.is_empty() || emoji.contains(&needle))
-            .collect();
+    /// Show or hide the column with each emoji's semver bump, next to its
+    /// code. Hidden by default.
+    pub fn set_show_semver(&mut self, show: bool) {
+        self.show_semver = show;
+    }
 
-        self.state
-            .select(adjust_selected(self.state.selected(), emojis.len()));
+    /// List the emojis under a header for each category while the search
+    /// is empty, rather than all in one run; see [`Emoji::category`]. Off
+    /// by default.
+    pub fn set_grouped(&mut self, grouped: bool) {
+        self.grouped = grouped;
+    }
 
-        FilteredView {
-            emojis,
+    /// Emoji glyphs to list first, in a "Suggested" section of their own,
+    /// while the search is empty. Glyphs that aren't in the list are
+    /// ignored.
+    pub fn set_suggested(&mut self, suggested: Vec<String>) {
+        self.suggested = suggested;
+    }
+
+    /// Emoji glyphs to list next, in a "Recent" section, like
+    /// [`Self::set_suggested`].
+    pub fn set_recent(&mut self, recent: Vec<String>) {
+        self.recent = recent;
+    }
+
+    /// The emojis toggled for picking together, in the order they were
+    /// toggled on; see [`FilteredView::toggle`].
+    pub fn toggled(&self) -> &[Emoji] {
+        &self.toggled
+    }
+
+    /// Untoggle all the emojis.
+    pub fn clear_toggled(&mut self) {
+        self.toggled.clear();
+    }
+
+    pub fn filtered_view(&mut self, search_text: &str) -> FilteredView<'_, '_> {
+        let mut matches = search::rank(self.emojis.iter(), search_text);
+        let mut sections = Vec::new();
+        if search_text.trim().is_empty() {
+            let mut rest = std::mem::take(&mut matches);
+            for (title, glyphs) in [("Suggested", &self.suggested), ("Recent", &self.recent)] {
+                let rank =
+                    |m: &Match<'_>| glyphs.iter().position(|glyph| glyph == m.emoji().emoji());
+                let (mut listed, others): (Vec<_>, Vec<_>) =
+                    rest.into_iter().partition(|m| rank(m).is_some());
+                rest = others;
+                if !listed.is_empty() {
+                    listed.sort_by_key(rank);
+                    sections.push(Section {
+                        title: Cow::Borrowed(title),
+                        start: matches.len(),
+                    });
+                    matches.extend(listed);
+                }
+            }
+            if self.grouped {
+                for (title, listed) in by_category(rest) {
+                    sections.push(Section {
+                        title: Cow::Owned(title),
+                        start: matches.len(),
+                    });
+                    matches.extend(listed);
+                }
+            } else {
+                if !sections.is_empty() && !rest.is_empty() {
+                    sections.push(Section {
+                        title: Cow::Borrowed("All"),
+                        start: matches.len(),
+                    });
+                }
+                matches.extend(rest);
+            }
+        }
+
+        let mut view = FilteredView {
+            matches,
+            sections,
             state: &mut self.state,
+            toggled: &mut self.toggled,
             colors: self.colors,
             source: self.source,
+            show_semver: self.show_semver,
+        };
+        // Reset the selection if the list went shorter than it, or it now
+        // lands on a section header.
+        match (view.selected_index(), view.matches.is_empty()) {
+            (_, true) => view.state.select(None),
+            (None, false) => view.select(0),
+            (Some(_), false) => (),
         }
+
+        view
     }
 }
 
-fn adjust_selected(selected: Option<usize>, list_len: usize) -> Option<usize> {
-    match (selected, list_len) {
-        (_, 0) => None,
-        (None, _) => Some(0),
-        // Reset the selection if the list goes shorter than the selected index.
-        (Some(selected), _) if selected >= list_len => Some(0),
-        (Some(_), _) => selected,
-    }
+/// A titled run of consecutive emojis in a [`FilteredView`], shown under a
+/// header row of its own.
+struct Section {
+    title: Cow<'static, str>,
+    /// Index of the section's first emoji.
+    start: usize,
 }
 
+/// The emojis matching a search, as laid out in the list.
+///
+/// Emoji indices, as taken by [`Self::get`] and [`Self::select`], count
+/// emojis only. The list's rows also include a header for each section, so
+/// the table's offset and [`Self::index_at_row`] work in rows instead.
 pub struct FilteredView<'s, 'c> {
-    emojis: Vec<&'s Emoji>,
+    matches: Vec<Match<'s>>,
+    sections: Vec<Section>,
     state: &'s mut TableState,
+    toggled: &'s mut Vec<Emoji>,
     colors: &'c Colors,
     source: EmojiSource,
+    show_semver: bool,
 }
 
 impl<'s> FilteredView<'s, '_> {
     pub fn selected(&self) -> Option<&Emoji> {
-        self.emojis.get(self.state.selected()?).copied()
+        self.get(self.selected_index()?)
     }
 
     pub fn get(&self, index: usize) -> Option<&'s Emoji> {
-        self.emojis.get(index).copied()
+        self.matches.get(index).map(Match::emoji)
     }
 
     pub fn visible_count(&self) -> usize {
-        self.emojis.len()
+        self.matches.len()
+    }
+
+    /// Number of rows in the list: one per emoji, plus the section headers.
+    pub fn row_count(&self) -> usize {
+        self.matches.len() + self.sections.len()
+    }
+
+    /// Index of the emoji on list row `row`, or `None` for a section header
+    /// or a row past the end.
+    pub fn index_at_row(&self, row: usize) -> Option<usize> {
+        let mut headers = 0;
+        for section in &self.sections {
+            let header_row = section.start + headers;
+            if row == header_row {
+                return None;
+            }
+            if row < header_row {
+                break;
+            }
+            headers += 1;
+        }
+        let index = row - headers;
+
+        (index < self.matches.len()).then_some(index)
     }
 
+    /// The first list row shown, counting section headers.
     pub fn offset(&self) -> usize {
         self.state.offset()
     }
@@ -103,34 +226,41 @@ impl<'s> FilteredView<'s, '_> {
     /// Move the selection to `index`. A no-op when the index is past the end
     /// of the filtered list, matching [`Self::get`]'s view of it.
     pub fn select(&mut self, index: usize) {
-        if index < self.emojis.len() {
-            self.state.select(Some(index));
+        if index >= self.matches.len() {
+            return;
+        }
+        let row = self.row_of(index);
+        self.state.select(Some(row));
+        // The table only scrolls as far as the selected row, which would
+        // leave the header of a section's first emoji out of sight.
+        if self.sections.iter().any(|s| s.start == index) && self.state.offset() >= row {
+            *self.state.offset_mut() = row - 1;
         }
     }
 
-    /// Move the selection one row up, wrapping to the last row. A no-op when
-    /// the filter matched nothing: there is no selection to move and no last
-    /// row to wrap onto.
+    /// Move the selection one emoji up, wrapping to the last one. A no-op
+    /// when the filter matched nothing: there is no selection to move and
+    /// no last emoji to wrap onto.
     pub fn move_up(&mut self) {
-        let Some(i) = self.state.selected() else {
+        let Some(i) = self.selected_index() else {
             return;
         };
-        let Some(last) = self.emojis.len().checked_sub(1) else {
+        let Some(last) = self.matches.len().checked_sub(1) else {
             return;
         };
-        self.state.select(Some(if i == 0 { last } else { i - 1 }));
+        self.select(if i == 0 { last } else { i - 1 });
     }
 
-    /// Move the selection one row down, wrapping to the first row. A no-op
+    /// Move the selection one emoji down, wrapping to the first one. A no-op
     /// when the filter matched nothing — see [`Self::move_up`].
     pub fn move_down(&mut self) {
-        let Some(i) = self.state.selected() else {
+        let Some(i) = self.selected_index() else {
             return;
         };
-        let Some(last) = self.emojis.len().checked_sub(1) else {
+        let Some(last) = self.matches.len().checked_sub(1) else {
             return;
         };
-        self.state.select(Some(if i == last { 0 } else { i + 1 }));
+        self.select(if i == last { 0 } else { i + 1 });
     }
 
     /// Scroll the visible window by `delta` rows, positive towards the end
@@ -142,19 +272,75 @@ impl<'s> FilteredView<'s, '_> {
     /// table widget scrolls itself back to whatever is selected on the next
     /// render, which would otherwise undo the scroll immediately.
     pub fn scroll_by(&mut self, delta: i32, viewport_rows: usize) {
-        let Some(last) = self.emojis.len().checked_sub(1) else {
+        let Some(last) = self.row_count().checked_sub(1) else {
             return;
         };
         let rows = viewport_rows.max(1);
-        let max_offset = self.emojis.len().saturating_sub(rows) as i64;
+        let max_offset = self.row_count().saturating_sub(rows) as i64;
         let offset = (self.state.offset() as i64 + delta as i64).clamp(0, max_offset) as usize;
         *self.state.offset_mut() = offset;
 
         if let Some(selected) = self.state.selected() {
             let bottom = (offset + rows - 1).min(last);
-            self.state.select(Some(selected.clamp(offset, bottom)));
+            let mut row = selected.clamp(offset, bottom);
+            // Headers aren't selectable; step onto a neighbouring emoji,
+            // which is never another header. Every header has an emoji
+            // below it, so in a window of a single row that one is brought
+            // into view instead.
+            if self.index_at_row(row).is_none() {
+                row = if row < bottom || row == offset {
+                    row + 1
+                } else {
+                    row - 1
+                };
+                if row > bottom {
+                    *self.state.offset_mut() = row + 1 - rows;
+                }
+            }
+            self.state.select(Some(row));
+        }
+    }
+
+    /// Toggle emoji `index` for picking along with others. Toggling
+    /// survives searches, so emojis can be found one after the other.
+    pub fn toggle(&mut self, index: usize) {
+        let Some(emoji) = self.get(index) else {
+            return;
+        };
+        match self.toggled.iter().position(|t| t.code() == emoji.code()) {
+            Some(position) => {
+                self.toggled.remove(position);
+            }
+            None => self.toggled.push(emoji.clone()),
         }
     }
+
+    /// Toggle the selected emoji; see [`Self::toggle`].
+    pub fn toggle_selected(&mut self) {
+        if let Some(index) = self.selected_index() {
+            self.toggle(index);
+        }
+    }
+
+    /// The toggled emojis, in the order they were toggled on.
+    pub fn toggled(&self) -> &[Emoji] {
+        self.toggled
+    }
+
+    /// Whether emoji `index` is toggled.
+    pub fn is_toggled(&self, index: usize) -> bool {
+        self.get(index)
+            .is_some_and(|emoji| self.toggled.iter().any(|t| t.code() == emoji.code()))
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        self.index_at_row(self.state.selected()?)
+    }
+
+    /// The list row emoji `index` is shown on.
+    fn row_of(&self, index: usize) -> usize {
+        index + self.sections.iter().filter(|s| s.start <= index).count()
+    }
 }
 
 impl Widget for &mut FilteredView<'_, '_> {
@@ -166,51 +352,167 @@ impl Widget for &mut FilteredView<'_, '_> {
         // placeholder that always consumes exactly `EMOJI_COLUMN_WIDTH`
         // cells per row.
         let source = self.source;
-        let emojis = self.emojis.iter().map(|emoji| {
+        let show_semver = self.show_semver;
+        // Underlined as well as coloured, so matches still stand out on the
+        // selected row, whose highlight style overrides the foreground.
+        let matched = Style::default()
+            .fg(self.colors.matched)
+            .add_modifier(Modifier::UNDERLINED);
+        let header = Style::default()
+            .fg(self.colors.border)
+            .add_modifier(Modifier::BOLD);
+        let mut sections = self.sections.iter().peekable();
+        let mut rows = Vec::with_capacity(self.row_count());
+        for (i, m) in self.matches.iter().enumerate() {
+            if let Some(section) = sections.next_if(|s| s.start == i) {
+                rows.push(
+                    Row::new([Cell::from(""), Cell::from(section.title.as_ref())]).style(header),
+                );
+            }
+            let emoji = m.emoji();
             let cell0 = match source {
                 EmojiSource::InCanvas => emoji.emoji(),
                 EmojiSource::Overlay => "",
             };
-            Row::new(vec![cell0, emoji.code(), emoji.description()])
-        });
-        let table = Table::new(
-            emojis,
-            [
-                Constraint::Length(EMOJI_COLUMN_WIDTH),
-                Constraint::Length(20),
-                Constraint::Fill(1),
-            ],
-        )
-        .block(
-            Block::default()
-                .title("Select an emoji")
-                .borders(Borders::ALL)
-                .padding(Padding {
-                    left: 1,
-                    right: 1,
-                    top: 1,
-                    bottom: 0,
-                }),
-        )
-        .style(Style::default().fg(self.colors.unselected))
-        .row_highlight_style(
-            Style::default()
-                .add_modifier(Modifier::BOLD)
-                .fg(self.colors.selected),
-        )
-        .highlight_symbol("❯ ")
-        // Always reserve the highlight-symbol gutter so column positions
-        // don't shift when the selection moves — the web overlay relies on
-        // a stable per-row x offset for the emoji column.
-        .highlight_spacing(HighlightSpacing::Always)
-        .column_spacing(2);
+            let mut cells = vec![
+                Cell::from(cell0),
+                Cell::from(highlight(emoji.code(), m.code_ranges(), matched)),
+            ];
+            if show_semver {
+                cells.push(Cell::from(emoji.semver().map_or("", |bump| bump.as_str())));
+            }
+            cells.push(Cell::from(highlight(
+                emoji.description(),
+                m.description_ranges(),
+                matched,
+            )));
+            rows.push(Row::new(cells));
+        }
+        let mut widths = vec![
+            Constraint::Length(EMOJI_COLUMN_WIDTH),
+            Constraint::Length(20),
+        ];
+        if show_semver {
+            widths.push(Constraint::Length(SEMVER_COLUMN_WIDTH));
+        }
+        widths.push(Constraint::Fill(1));
+        let title = match self.toggled.len() {
+            0 => Cow::Borrowed(TITLE),
+            n => Cow::Owned(format!("{TITLE} ({n} toggled)")),
+        };
+        let table = Table::new(rows, widths)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .padding(Padding {
+                        left: 1,
+                        right: 1,
+                        top: 1,
+                        bottom: 0,
+                    }),
+            )
+            .style(Style::default().fg(self.colors.unselected))
+            .row_highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(self.colors.selected),
+            )
+            .highlight_symbol("❯ ")
+            // Always reserve the highlight-symbol gutter so column positions
+            // don't shift when the selection moves — the web overlay relies on
+            // a stable per-row x offset for the emoji column.
+            .highlight_spacing(HighlightSpacing::Always)
+            .column_spacing(2);
         StatefulWidget::render(table, area, buf, self.state);
+
+        // Mark the toggled rows in the gutter, next to where the selected
+        // one has its highlight symbol. Rows start inside the border and the
+        // top padding.
+        let x = area.x + 2 + HIGHLIGHT_GUTTER_WIDTH - 1;
+        let top = area.y + 2;
+        let bottom = area.bottom().saturating_sub(1);
+        let offset = self.state.offset();
+        for y in top..bottom {
+            let row = offset + usize::from(y - top);
+            if self.index_at_row(row).is_some_and(|i| self.is_toggled(i)) {
+                let style = Style::default().fg(self.colors.matched);
+                buf.set_string(x, y, TOGGLED_MARKER, style);
+            }
+        }
     }
 }
 
+/// `matches` split up by category, each under its title: the database's
+/// categories in their order first, then any others in the order they come
+/// in, and last the emojis without one under "Other".
+fn by_category(matches: Vec<Match<'_>>) -> Vec<(String, Vec<Match<'_>>)> {
+    let mut groups: Vec<(Option<&str>, Vec<Match<'_>>)> = Vec::new();
+    for m in matches {
+        let category = m.emoji().category();
+        match groups.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, listed)) => listed.push(m),
+            None => groups.push((category, vec![m])),
+        }
+    }
+    // Stable, so the other categories keep their order.
+    groups.sort_by_key(|(category, _)| match category {
+        Some(category) => CATEGORIES
+            .iter()
+            .position(|known| known == category)
+            .unwrap_or(CATEGORIES.len()),
+        None => CATEGORIES.len() + 1,
+    });
+
+    groups
+        .into_iter()
+        .map(|(category, listed)| {
+            let title = match category {
+                Some(category) => capitalized(category),
+                None => OTHER_TITLE.to_string(),
+            };
+            (title, listed)
+        })
+        .collect()
+}
+
+/// `text` with its first letter in upper case, e.g. "Fixes" for `fixes`.
+fn capitalized(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// `text` with the byte `ranges` of it picked out in `style`.
+fn highlight<'t>(text: &'t str, ranges: &[Range<usize>], style: Style) -> Line<'t> {
+    let mut spans = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut end = 0;
+    for range in ranges {
+        if range.start > end {
+            spans.push(Span::raw(&text[end..range.start]));
+        }
+        spans.push(Span::styled(&text[range.clone()], style));
+        end = range.end;
+    }
+    if end < text.len() {
+        spans.push(Span::raw(&text[end..]));
+    }
+
+    Line::from(spans)
+}
+
 /// Cell width of the leading emoji column in each row. Native renders the
 /// emoji glyph through the terminal font; overlay-rendering frontends paint
 /// their own glyph over this region.
 pub const EMOJI_COLUMN_WIDTH: u16 = 3;
+/// Cell width of the optional semver column: fits the longest bump name.
+const SEMVER_COLUMN_WIDTH: u16 = 5;
 /// Cell width of the gutter reserved for the row highlight symbol ("❯ ").
 pub const HIGHLIGHT_GUTTER_WIDTH: u16 = 2;
+const TITLE: &str = "Select an emoji";
+/// Heads the emojis without a category when grouping.
+const OTHER_TITLE: &str = "Other";
+/// Marks toggled rows in the last cell of the highlight gutter.
+const TOGGLED_MARKER: &str = "•";
@@ -0,0 +1,261 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use crate::emoji::Emoji;
+
+/// How a picked emoji is written out, e.g. `{emoji} ` for `🐛 ` or
+/// `{code} ` for gitmoji-cli's `:bug: `.
+///
+/// Placeholders are `{emoji}`, `{code}`, `{name}`, `{description}`,
+/// `{type}`, the emoji's Conventional Commits type (empty if it has none),
+/// and `{scope}`. Parentheses around an empty `{scope}` go too, so
+/// `{type}({scope}): ` gives `fix: ` without one. `{{` and `}}` stand for
+/// literal braces. Anything else is kept as it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// The template with `emoji` and `scope` filled in.
+    pub fn render(&self, emoji: &Emoji, scope: Option<&str>) -> String {
+        self.render_many(std::slice::from_ref(emoji), scope)
+    }
+
+    /// The template with several `emojis` and `scope` filled in: the glyphs
+    /// and codes one after the other, the names joined with commas, and the
+    /// description and type of the first emoji that has one.
+    pub fn render_many(&self, emojis: &[Emoji], scope: Option<&str>) -> String {
+        let scope = scope.unwrap_or_default();
+        let mut out = String::new();
+        // Whether the next text starts with the `)` of an empty scope.
+        let mut closing = false;
+        for (i, part) in self.parts.iter().enumerate() {
+            let text: Cow<'_, str> = match part {
+                Part::Text(text) if closing => text[1..].into(),
+                Part::Text(text) => text.into(),
+                Part::Placeholder(Placeholder::Scope) if scope.is_empty() => {
+                    let next = self.parts.get(i + 1);
+                    if out.ends_with('(')
+                        && matches!(next, Some(Part::Text(next)) if next.starts_with(')'))
+                    {
+                        out.pop();
+                        closing = true;
+                    }
+                    continue;
+                }
+                Part::Placeholder(Placeholder::Emoji) => {
+                    emojis.iter().map(Emoji::emoji).collect::<String>().into()
+                }
+                Part::Placeholder(Placeholder::Code) => {
+                    emojis.iter().map(Emoji::code).collect::<String>().into()
+                }
+                Part::Placeholder(Placeholder::Name) => {
+                    let names: Vec<_> = emojis.iter().map(Emoji::name).collect();
+                    names.join(",").into()
+                }
+                Part::Placeholder(Placeholder::Description) => emojis
+                    .first()
+                    .map(Emoji::description)
+                    .unwrap_or_default()
+                    .into(),
+                Part::Placeholder(Placeholder::Type) => emojis
+                    .iter()
+                    .find_map(Emoji::commit_type)
+                    .unwrap_or_default()
+                    .into(),
+                Part::Placeholder(Placeholder::Scope) => scope.into(),
+            };
+            out.push_str(&text);
+            closing = false;
+        }
+
+        out
+    }
+}
+
+impl Default for Template {
+    /// The glyph and a space, ready for the rest of the subject line.
+    fn default() -> Self {
+        Self {
+            parts: vec![
+                Part::Placeholder(Placeholder::Emoji),
+                Part::Text(" ".to_string()),
+            ],
+        }
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut rest = s;
+        while let Some(i) = rest.find(['{', '}']) {
+            text.push_str(&rest[..i]);
+            let brace = &rest[i..i + 1];
+            rest = &rest[i + 1..];
+            if let Some(after) = rest.strip_prefix(brace) {
+                text.push_str(brace);
+                rest = after;
+                continue;
+            }
+            if brace == "}" {
+                return Err(TemplateError::Unbalanced(s.to_string()));
+            }
+
+            let end = rest
+                .find('}')
+                .ok_or_else(|| TemplateError::Unbalanced(s.to_string()))?;
+            let placeholder = match &rest[..end] {
+                "emoji" => Placeholder::Emoji,
+                "code" => Placeholder::Code,
+                "name" => Placeholder::Name,
+                "description" => Placeholder::Description,
+                "type" => Placeholder::Type,
+                "scope" => Placeholder::Scope,
+                unknown => return Err(TemplateError::UnknownPlaceholder(unknown.to_string())),
+            };
+            if !text.is_empty() {
+                parts.push(Part::Text(std::mem::take(&mut text)));
+            }
+            parts.push(Part::Placeholder(placeholder));
+            rest = &rest[end + 1..];
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Self { parts })
+    }
+}
+
+impl TryFrom<String> for Template {
+    type Error = TemplateError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Why a string isn't a [`Template`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A `{…}` that names nothing the template can fill in.
+    UnknownPlaceholder(String),
+    /// A brace without its pair; literal ones are written `{{` and `}}`.
+    Unbalanced(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownPlaceholder(name) => write!(
+                f,
+                "unknown placeholder `{{{name}}}` (expected `{{emoji}}`, `{{code}}`, `{{name}}`, \
+                 `{{description}}`, `{{type}}` or `{{scope}}`)"
+            ),
+            TemplateError::Unbalanced(template) => write!(
+                f,
+                "unbalanced braces in `{template}` (write `{{{{` and `}}}}` for literal ones)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    Emoji,
+    Code,
+    Name,
+    Description,
+    Type,
+    Scope,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lookup;
+
+    fn render(template: &str) -> String {
+        let bug = lookup("bug").unwrap();
+        template.parse::<Template>().unwrap().render(bug, None)
+    }
+
+    #[test]
+    fn placeholders_are_filled_in() {
+        assert_eq!(render("{emoji} "), "🐛 ");
+        assert_eq!(render("{code} "), ":bug: ");
+        assert_eq!(render("fix: {emoji} "), "fix: 🐛 ");
+        assert_eq!(render("[{name}] "), "[bug] ");
+        assert_eq!(render("{description}"), "Fix a bug.");
+        assert_eq!(render("{type}: {emoji} "), "fix: 🐛 ");
+        assert_eq!(render("plain"), "plain");
+    }
+
+    #[test]
+    fn several_emojis_are_joined() {
+        let emojis = [
+            lookup("memo").unwrap().clone(),
+            lookup("bug").unwrap().clone(),
+        ];
+        let render = |template: &str| {
+            let template: Template = template.parse().unwrap();
+            template.render_many(&emojis, None)
+        };
+        assert_eq!(render("{emoji} "), "📝🐛 ");
+        assert_eq!(render("{code} "), ":memo::bug: ");
+        assert_eq!(render("[{name}]"), "[memo,bug]");
+        assert_eq!(render("{description}"), emojis[0].description());
+        assert_eq!(render("{type}: {emoji} "), "docs: 📝🐛 ");
+    }
+
+    #[test]
+    fn empty_scopes_take_their_parentheses_along() {
+        let bug = lookup("bug").unwrap();
+        let template: Template = "{type}({scope}): {emoji} ".parse().unwrap();
+        assert_eq!(template.render(bug, Some("core")), "fix(core): 🐛 ");
+        assert_eq!(template.render(bug, None), "fix: 🐛 ");
+        assert_eq!(template.render(bug, Some("")), "fix: 🐛 ");
+        assert_eq!(render("[{scope}] {emoji}"), "[] 🐛");
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(render("{{{code}}} "), "{:bug:} ");
+        assert_eq!(render("}}{{"), "}{");
+    }
+
+    #[test]
+    fn default_is_the_glyph_and_a_space() {
+        assert_eq!(Template::default(), "{emoji} ".parse().unwrap());
+    }
+
+    #[test]
+    fn mistakes_are_reported() {
+        let parse = |s: &str| s.parse::<Template>().unwrap_err();
+        assert_eq!(
+            parse("{kind} "),
+            TemplateError::UnknownPlaceholder("kind".to_string())
+        );
+        assert_eq!(
+            parse("{emoji"),
+            TemplateError::Unbalanced("{emoji".to_string())
+        );
+        assert_eq!(
+            parse("emoji}"),
+            TemplateError::Unbalanced("emoji}".to_string())
+        );
+    }
+}
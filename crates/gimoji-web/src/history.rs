@@ -0,0 +1,50 @@
+use gimoji_core::{history, History, HistoryEntry};
+
+/// The picker's history of picked emojis, kept in the browser's
+/// `localStorage` so it survives reloads.
+pub struct LocalStorageHistory;
+
+#[derive(Debug)]
+pub struct Error(String);
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl LocalStorageHistory {
+    /// `localStorage` itself, which browsers withhold in some contexts
+    /// (e.g. sandboxed frames or with storage disabled).
+    fn storage() -> Result<web_sys::Storage, Error> {
+        let window = web_sys::window().ok_or_else(|| Error("no window".into()))?;
+
+        window
+            .local_storage()
+            .map_err(|e| Error(format!("{e:?}")))?
+            .ok_or_else(|| Error("no localStorage".into()))
+    }
+}
+
+impl History for LocalStorageHistory {
+    type Error = Error;
+
+    fn load(&mut self) -> Result<Vec<HistoryEntry>, Self::Error> {
+        let text = Self::storage()?
+            .get_item(STORAGE_KEY)
+            .map_err(|e| Error(format!("{e:?}")))?;
+
+        Ok(text.map(|t| history::parse(&t)).unwrap_or_default())
+    }
+
+    fn save(&mut self, entries: &[HistoryEntry]) -> Result<(), Self::Error> {
+        Self::storage()?
+            .set_item(STORAGE_KEY, &history::format(entries))
+            .map_err(|e| Error(format!("{e:?}")))
+    }
+}
+
+/// The `localStorage` key the history is stored under.
+const STORAGE_KEY: &str = "gimoji-history";
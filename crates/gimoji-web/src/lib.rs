@@ -1,7 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
 use canvas_backend::CanvasBackend;
-use gimoji_core::{Action, App, Outcome, EMOJIS};
+use gimoji_core::{Action, App, History, Outcome, EMOJIS};
 use ratatui::{
     layout::{Position, Rect},
     Terminal,
@@ -23,6 +23,7 @@ const MAX_PICKER_ROWS: u16 = 36;
 mod canvas_backend;
 mod clipboard;
 mod color_scheme;
+mod history;
 mod input;
 mod text_input;
 
@@ -80,7 +81,12 @@ pub fn run() -> Result<(), JsValue> {
     // unicode-width-based column accounting; we paint the glyphs on top
     // of the canvas in the RAF loop instead. See
     // `CanvasBackend::paint_emoji_overlay`.
-    let app = App::with_emoji_overlay(EMOJIS, colors);
+    let mut app = App::with_emoji_overlay(EMOJIS, colors);
+    // A picker without its history still picks, so don't fail over it.
+    match history::LocalStorageHistory.load() {
+        Ok(entries) => app.set_history(entries),
+        Err(e) => log_history_failure("load", &e),
+    }
 
     let backend = CanvasBackend::new(CANVAS_ID)
         .map_err(|e| JsValue::from_str(&format!("CanvasBackend init failed: {e}")))?;
@@ -235,7 +241,7 @@ fn install_raf_loop(
                             .app
                             .visible_emojis()
                             .iter()
-                            .map(|ve| (ve.cell, ve.emoji))
+                            .map(|ve| (ve.cell, &*ve.emoji))
                             .chain(s.app.toast_overlay_emoji());
                         term.backend_mut().paint_emoji_overlays(band, overlays);
                         None
@@ -717,9 +723,13 @@ fn drive(state: &Rc<RefCell<State>>, action: Action) {
         s.text_input.set_value(s.app.search_text());
         outcome
     };
-    let Outcome::Picked(text) = outcome else {
+    let Outcome::Picked(emoji) = outcome else {
         return;
     };
+    let text = emoji.emoji().to_string();
+    if let Err(e) = history::LocalStorageHistory.save(state.borrow().app.history()) {
+        log_history_failure("save", &e);
+    }
     // Bind the result before matching on it: the failure arm re-borrows the
     // state, which would panic while the borrow behind this call is live.
     let started = state.borrow().clipboard.copy(&text);
@@ -748,3 +758,11 @@ fn report_copy_failure(state: &Rc<RefCell<State>>, text: &str, cause: &JsValue)
     );
     state.borrow_mut().app.show_toast(COPY_FAILED_PREFIX, "");
 }
+
+/// Log a history that couldn't be read or written. Nothing goes on screen:
+/// the pick itself still worked.
+fn log_history_failure(what: &str, e: &history::Error) {
+    web_sys::console::warn_1(&JsValue::from_str(&format!(
+        "failed to {what} the history: {e}"
+    )));
+}
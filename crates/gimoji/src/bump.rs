@@ -0,0 +1,178 @@
+use std::{error::Error, fmt};
+
+use gimoji_core::{detect_prefix, SemverBump};
+
+use crate::git;
+
+/// Work out the next version from the emojis of the commits since the last tag.
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Only consider tags matching this glob (e.g. `gimoji-core-*`).
+    #[arg(long = "match", value_name = "PATTERN")]
+    pattern: Option<String>,
+
+    /// Only print the next version.
+    #[arg(long)]
+    short: bool,
+}
+
+pub fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let tag = git::last_tag(args.pattern.as_deref())?;
+    let (current, range) = match &tag {
+        Some(tag) => (Version::from_tag(tag)?, format!("{tag}..HEAD")),
+        None => (Version::default(), String::from("HEAD")),
+    };
+    let subjects = git::subjects(&range)?;
+    let bump = next_bump(subjects.iter().map(String::as_str));
+    let next = bump.map_or(current, |bump| current.bump(bump));
+
+    if args.short {
+        println!("{next}");
+    } else if let Some(bump) = bump {
+        println!("{bump}: {current} -> {next}");
+    } else {
+        println!("No release needed: none of the commits since {current} bump the version");
+    }
+
+    Ok(())
+}
+
+/// The most significant bump called for by the leading emojis of `subjects`,
+/// or `None` if none of them affects the version.
+pub fn next_bump<'s>(subjects: impl IntoIterator<Item = &'s str>) -> Option<SemverBump> {
+    subjects
+        .into_iter()
+        .filter_map(detect_prefix)
+        .filter_map(|(emoji, _)| emoji.semver())
+        .max()
+}
+
+/// A `major.minor.patch` release version.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+impl Version {
+    /// Parse the version out of a release tag. Anything before the first
+    /// digit is taken to be a prefix, so `1.4.0`, `v1.4.0` and
+    /// `gimoji-core-1.4.0` all work; pre-release and build suffixes are
+    /// ignored.
+    pub fn from_tag(tag: &str) -> Result<Self, Box<dyn Error>> {
+        let invalid = || format!("Tag `{tag}` doesn't carry a `major.minor.patch` version");
+        let start = tag.find(|c: char| c.is_ascii_digit()).ok_or_else(invalid)?;
+        let core = tag[start..]
+            .split(['-', '+'])
+            .next()
+            .expect("`split` yields at least one item");
+        let mut parts = core.split('.').map(str::parse::<u64>);
+        let (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid().into());
+        };
+
+        Ok(Self {
+            major,
+            minor,
+            patch,
+        })
+    }
+
+    /// The version after a release with the given bump.
+    ///
+    /// Before 1.0.0 everything shifts down a place, as Cargo reads versions:
+    /// a breaking change bumps the minor version and a feature the patch.
+    pub fn bump(self, bump: SemverBump) -> Self {
+        let bump = match (self.major, bump) {
+            (0, SemverBump::Major) => SemverBump::Minor,
+            (0, _) => SemverBump::Patch,
+            (_, bump) => bump,
+        };
+        match bump {
+            SemverBump::Major => Self {
+                major: self.major + 1,
+                minor: 0,
+                patch: 0,
+            },
+            SemverBump::Minor => Self {
+                minor: self.minor + 1,
+                patch: 0,
+                ..self
+            },
+            SemverBump::Patch => Self {
+                patch: self.patch + 1,
+                ..self
+            },
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(tag: &str) -> Version {
+        Version::from_tag(tag).unwrap()
+    }
+
+    #[test]
+    fn tags_with_prefixes_and_suffixes_parse() {
+        assert_eq!(version("1.4.0").to_string(), "1.4.0");
+        assert_eq!(version("v2.0.1").to_string(), "2.0.1");
+        assert_eq!(version("gimoji-core-1.4.0").to_string(), "1.4.0");
+        assert_eq!(version("v1.0.0-rc.1+build.5").to_string(), "1.0.0");
+    }
+
+    #[test]
+    fn tags_without_a_full_version_are_rejected() {
+        assert!(Version::from_tag("release").is_err());
+        assert!(Version::from_tag("v1.4").is_err());
+        assert!(Version::from_tag("1.4.0.1").is_err());
+    }
+
+    #[test]
+    fn bumps_reset_the_less_significant_parts() {
+        let v = version("1.4.2");
+        assert_eq!(v.bump(SemverBump::Major).to_string(), "2.0.0");
+        assert_eq!(v.bump(SemverBump::Minor).to_string(), "1.5.0");
+        assert_eq!(v.bump(SemverBump::Patch).to_string(), "1.4.3");
+    }
+
+    #[test]
+    fn pre_1_0_bumps_shift_down_a_place() {
+        let v = version("0.3.1");
+        assert_eq!(v.bump(SemverBump::Major).to_string(), "0.4.0");
+        assert_eq!(v.bump(SemverBump::Minor).to_string(), "0.3.2");
+        assert_eq!(v.bump(SemverBump::Patch).to_string(), "0.3.2");
+    }
+
+    #[test]
+    fn the_most_significant_bump_wins() {
+        let subjects = ["🐛 Fix a crash", "📝 Document it", "✨ Add a feature"];
+        assert_eq!(next_bump(subjects), Some(SemverBump::Minor));
+        assert_eq!(
+            next_bump([":bug: Fix a crash", "💥 Drop the old API"]),
+            Some(SemverBump::Major)
+        );
+    }
+
+    #[test]
+    fn commits_without_semver_emojis_need_no_release() {
+        assert_eq!(next_bump(["📝 Document it", "Plain subject"]), None);
+        assert_eq!(next_bump([]), None);
+    }
+
+    #[test]
+    fn emojis_past_the_start_of_the_subject_are_ignored() {
+        assert_eq!(next_bump(["Revert \"✨ Add a feature\""]), None);
+    }
+}
@@ -0,0 +1,252 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt::Write,
+};
+
+use gimoji_core::{detect_prefix, EMOJIS};
+
+use crate::git;
+
+/// Render a Keep a Changelog section from the commits in a git range, grouped by their emojis.
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The commits to cover, e.g. `1.3.0..HEAD`. Defaults to those since the last tag.
+    range: Option<String>,
+
+    /// Only consider tags matching this glob (e.g. `gimoji-core-*`) for the default range.
+    #[arg(long = "match", value_name = "PATTERN")]
+    pattern: Option<String>,
+
+    /// The version being released. Without it, the section is headed "Unreleased".
+    #[arg(long, value_name = "VERSION")]
+    release: Option<String>,
+
+    /// List commits starting with EMOJI (glyph, `:code:` or name) under SECTION. Repeatable.
+    ///
+    /// An empty SECTION leaves those commits out altogether.
+    #[arg(long, value_name = "EMOJI=SECTION", value_parser = parse_group)]
+    group: Vec<(&'static str, String)>,
+}
+
+pub fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let range = match &args.range {
+        Some(range) => range.clone(),
+        None => match git::last_tag(args.pattern.as_deref())? {
+            Some(tag) => format!("{tag}..HEAD"),
+            None => String::from("HEAD"),
+        },
+    };
+    let heading = match &args.release {
+        Some(version) => match git::last_commit_date(&range)? {
+            Some(date) => format!("{version} - {date}"),
+            None => version.clone(),
+        },
+        None => String::from("Unreleased"),
+    };
+
+    let mut sections = Sections::default();
+    for (code, section) in &args.group {
+        sections.set(code, section);
+    }
+    let subjects = git::subjects(&range)?;
+    print!("{}", render(&heading, &subjects, &sections));
+
+    Ok(())
+}
+
+/// Render the changelog section for `subjects`, listed in the given order
+/// under the section their leading emoji maps to.
+pub fn render(heading: &str, subjects: &[String], sections: &Sections) -> String {
+    let mut grouped: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for subject in subjects {
+        let subject = subject.trim();
+        let (section, rest) = match detect_prefix(subject) {
+            Some((emoji, len)) => (sections.get(emoji.code()), &subject[len..]),
+            None => (Some(OTHER_SECTION), subject),
+        };
+        // Emoji-only subjects say nothing worth a changelog line.
+        let Some(section) = section.filter(|_| !rest.trim().is_empty()) else {
+            continue;
+        };
+        let entry = format!("{}.", subject.trim_end_matches('.'));
+        grouped.entry(section).or_default().push(entry);
+    }
+
+    let mut out = format!("## {heading}\n");
+    for (section, entries) in grouped {
+        let _ = write!(out, "\n### {section}\n");
+        for entry in entries {
+            let _ = writeln!(out, "- {entry}");
+        }
+    }
+
+    out
+}
+
+/// Which changelog section commits go under, by the `:code:` of their
+/// leading emoji.
+#[derive(Debug, Clone)]
+pub struct Sections(HashMap<&'static str, String>);
+
+impl Sections {
+    /// List commits with the emoji of `code` under `section`, or leave them
+    /// out if `section` is empty.
+    pub fn set(&mut self, code: &'static str, section: &str) {
+        self.0.insert(code, section.to_string());
+    }
+
+    /// The section for commits with the emoji of `code`, or `None` if they
+    /// are left out. Emojis without a section of their own go under "Other".
+    pub fn get(&self, code: &str) -> Option<&str> {
+        match self.0.get(code) {
+            Some(section) if section.is_empty() => None,
+            Some(section) => Some(section),
+            None => Some(OTHER_SECTION),
+        }
+    }
+}
+
+impl Default for Sections {
+    /// The grouping this repository's own changelog uses, extended over the
+    /// rest of the emoji database.
+    fn default() -> Self {
+        let sections = DEFAULT_SECTIONS
+            .iter()
+            .map(|(code, section)| (*code, section.to_string()))
+            .collect();
+
+        Self(sections)
+    }
+}
+
+fn parse_group(group: &str) -> Result<(&'static str, String), String> {
+    let (emoji, section) = group
+        .split_once('=')
+        .ok_or_else(|| format!("expected `EMOJI=SECTION`, got `{group}`"))?;
+    let emoji = EMOJIS
+        .iter()
+        .find(|e| [e.emoji(), e.code(), e.name()].contains(&emoji))
+        .ok_or_else(|| format!("unknown emoji `{emoji}`"))?;
+
+    Ok((emoji.code(), section.trim().to_string()))
+}
+
+const OTHER_SECTION: &str = "Other";
+
+const DEFAULT_SECTIONS: &[(&str, &str)] = &[
+    // Release commits.
+    (":bookmark:", ""),
+    (":sparkles:", "Added"),
+    (":tada:", "Added"),
+    (":construction_worker:", "Added"),
+    (":triangular_flag_on_post:", "Added"),
+    (":bug:", "Fixed"),
+    (":ambulance:", "Fixed"),
+    (":adhesive_bandage:", "Fixed"),
+    (":goal_net:", "Fixed"),
+    (":bomb:", "Fixed"),
+    (":pencil2:", "Fixed"),
+    (":rotating_light:", "Fixed"),
+    (":zap:", "Performance"),
+    (":memo:", "Documentation"),
+    (":bulb:", "Documentation"),
+    (":page_facing_up:", "Documentation"),
+    (":recycle:", "Changed"),
+    (":art:", "Changed"),
+    (":truck:", "Changed"),
+    (":wrench:", "Changed"),
+    (":building_construction:", "Changed"),
+    (":lipstick:", "Changed"),
+    (":alien:", "Changed"),
+    (":children_crossing:", "Changed"),
+    (":wheelchair:", "Changed"),
+    (":speech_balloon:", "Changed"),
+    (":globe_with_meridians:", "Changed"),
+    (":boom:", "Breaking"),
+    (":fire:", "Removed"),
+    (":coffin:", "Removed"),
+    (":wastebasket:", "Deprecated"),
+    (":heavy_plus_sign:", "Dependencies"),
+    (":heavy_minus_sign:", "Dependencies"),
+    (":pushpin:", "Dependencies"),
+    (":arrow_up:", "Dependencies"),
+    (":arrow_down:", "Dependencies"),
+    (":lock:", "Security"),
+    (":passport_control:", "Security"),
+    (":white_check_mark:", "Testing"),
+    (":test_tube:", "Testing"),
+    (":green_heart:", "CI"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subjects(subjects: &[&str]) -> Vec<String> {
+        subjects.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn commits_are_grouped_by_emoji_in_alphabetical_sections() {
+        let subjects = subjects(&[
+            "🐛 Stop arrow keys panicking",
+            "✨ Add a web frontend.",
+            ":sparkles: Add a shared `App`",
+            "⬆️ Update clap to v4.6.3",
+        ]);
+        assert_eq!(
+            render("1.4.0 - 2026-08-12", &subjects, &Sections::default()),
+            "## 1.4.0 - 2026-08-12\n\
+             \n\
+             ### Added\n\
+             - ✨ Add a web frontend.\n\
+             - :sparkles: Add a shared `App`.\n\
+             \n\
+             ### Dependencies\n\
+             - ⬆️ Update clap to v4.6.3.\n\
+             \n\
+             ### Fixed\n\
+             - 🐛 Stop arrow keys panicking.\n"
+        );
+    }
+
+    #[test]
+    fn commits_without_a_known_emoji_go_under_other() {
+        let subjects = subjects(&["Tweak things", "🙈 Ignore node_modules"]);
+        assert_eq!(
+            render("Unreleased", &subjects, &Sections::default()),
+            "## Unreleased\n\n### Other\n- Tweak things.\n- 🙈 Ignore node_modules.\n"
+        );
+    }
+
+    #[test]
+    fn release_and_emoji_only_commits_are_left_out() {
+        let subjects = subjects(&["🔖 Release 1.4.0", "🎨", "🐛 Fix it"]);
+        assert_eq!(
+            render("Unreleased", &subjects, &Sections::default()),
+            "## Unreleased\n\n### Fixed\n- 🐛 Fix it.\n"
+        );
+    }
+
+    #[test]
+    fn groups_can_be_overridden() {
+        let mut sections = Sections::default();
+        let (code, section) = parse_group("👷=CI").unwrap();
+        sections.set(code, &section);
+        let (code, section) = parse_group("bug=").unwrap();
+        sections.set(code, &section);
+
+        let subjects = subjects(&["👷 Lint commit messages", "🐛 Fix it"]);
+        assert_eq!(
+            render("Unreleased", &subjects, &sections),
+            "## Unreleased\n\n### CI\n- 👷 Lint commit messages.\n"
+        );
+    }
+
+    #[test]
+    fn groups_for_unknown_emojis_are_rejected() {
+        assert!(parse_group(":nope:=Added").is_err());
+        assert!(parse_group("Added").is_err());
+    }
+}
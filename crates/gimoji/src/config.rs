@@ -0,0 +1,658 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    error::Error,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use gimoji_core::{Emoji, SemverBump, Template, EMOJIS};
+use serde::{Deserialize, Deserializer};
+
+use crate::{event_to_action::Key, git, suggest, ColorScheme};
+
+/// Settings from the user's `~/.config/gimoji/config.toml` and the
+/// repository's `.gimoji.toml`, the latter taking precedence so a team can
+/// version its conventions alongside the code.
+///
+/// Every setting is optional; whatever neither file sets keeps gimoji's
+/// built-in default. Command line options and environment variables in
+/// turn override both files.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Color scheme to use instead of detecting the terminal's.
+    pub color_scheme: Option<ColorScheme>,
+    /// What the hook puts in front of the commit message, and `--stdout`
+    /// prints. Defaults to the [`Prompts::default_prefix`] for the hook and
+    /// the bare glyph for `--stdout`.
+    #[serde(deserialize_with = "parsed")]
+    pub prefix: Option<Template>,
+    /// Whether the picker lists the emojis under a header for each
+    /// category. Defaults to `false`.
+    pub group_by_category: Option<bool>,
+    pub keybindings: Keybindings,
+    pub prompts: Prompts,
+    pub emojis: Emojis,
+    pub suggestions: Suggestions,
+    pub hook: Hook,
+}
+
+/// Keys for each picker action, replacing that action's default keys.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Keybindings {
+    /// Pick the selected emoji. Defaults to `enter`.
+    pub pick: Option<Vec<Key>>,
+    /// Clear the search, or quit if it's empty. Defaults to `esc`.
+    pub cancel: Option<Vec<Key>>,
+    /// Clear the search. Unbound by default.
+    pub clear: Option<Vec<Key>>,
+    /// Defaults to `up`.
+    pub move_up: Option<Vec<Key>>,
+    /// Defaults to `down`.
+    pub move_down: Option<Vec<Key>>,
+    /// Toggle the selected emoji, to pick several at once. Defaults to
+    /// `tab`. A key that types text, like `space`, only toggles while the
+    /// search is empty.
+    pub toggle: Option<Vec<Key>>,
+}
+
+/// What to ask for after the emoji, for the rest of the subject line; only
+/// when the message has no subject yet.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Prompts {
+    /// A scope, for `{scope}` in the prefix. Defaults to `false`.
+    pub scope: Option<bool>,
+    /// A one-line summary, following the prefix. Defaults to `false`.
+    pub summary: Option<bool>,
+}
+
+impl Prompts {
+    /// The prefix for when none is set: the glyph and a space, or with a
+    /// scope to ask for, a Conventional Commits header in front of those
+    /// for the scope to go in.
+    pub fn default_prefix(&self) -> Template {
+        match self.scope {
+            Some(true) => SCOPED_PREFIX.parse().expect("the scoped prefix parses"),
+            _ => Template::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Emojis {
+    /// Whether to offer the database's emojis, or only the `extra` ones.
+    /// Defaults to `true`.
+    pub builtin: Option<bool>,
+    /// The project's own emojis. One with the same `:code:` as a database
+    /// emoji replaces it.
+    pub extra: Option<Vec<ExtraEmoji>>,
+    /// Emojis to leave out of the picker, by glyph, `:code:` or name.
+    pub disabled: Option<Vec<String>>,
+    /// Conventional Commits types for emojis, by glyph, `:code:` or name,
+    /// in place of the ones they come with.
+    #[serde(deserialize_with = "commit_types")]
+    pub types: Option<BTreeMap<String, String>>,
+}
+
+impl Emojis {
+    /// The emojis to offer: the database's, unless turned off, merged with
+    /// the extra ones and without the disabled ones.
+    pub fn picker_emojis(&self) -> Cow<'static, [Emoji]> {
+        let builtin = self.builtin.unwrap_or(true);
+        let disabled = self.disabled.as_deref().unwrap_or_default();
+        if builtin && self.extra.is_none() && disabled.is_empty() && self.types.is_none() {
+            return Cow::Borrowed(EMOJIS);
+        }
+
+        let mut emojis = if builtin { EMOJIS.to_vec() } else { Vec::new() };
+        for extra in self.extra_emojis() {
+            match emojis.iter_mut().find(|emoji| emoji.code() == extra.code()) {
+                Some(emoji) => *emoji = extra,
+                None => emojis.push(extra),
+            }
+        }
+        if self.types.is_some() {
+            for emoji in &mut emojis {
+                *emoji = self.typed(emoji);
+            }
+        }
+        for query in disabled {
+            let before = emojis.len();
+            emojis.retain(|emoji| !names(query, emoji));
+            if emojis.len() == before {
+                // Perhaps one this version of the database doesn't have,
+                // which is left out already.
+                eprintln!("WARNING: Ignoring unknown disabled emoji `{query}`");
+            }
+        }
+
+        emojis.into()
+    }
+
+    /// The `extra` emojis, ready for the picker.
+    pub fn extra_emojis(&self) -> Vec<Emoji> {
+        self.extra
+            .iter()
+            .flatten()
+            .map(|extra| {
+                Emoji::new(
+                    extra.emoji.clone(),
+                    extra.code.clone(),
+                    extra.description.clone(),
+                )
+                .with_semver(extra.semver)
+                .with_commit_type(extra.commit_type.clone().map(Into::into))
+                .with_category(extra.category.clone().map(Into::into))
+            })
+            .collect()
+    }
+
+    /// `emoji` with the Conventional Commits type `types` gives it, if any.
+    pub fn typed(&self, emoji: &Emoji) -> Emoji {
+        let commit_type = self
+            .types
+            .iter()
+            .flatten()
+            .find(|(query, _)| names(query, emoji));
+
+        match commit_type {
+            Some((_, commit_type)) => emoji
+                .clone()
+                .with_commit_type(Some(commit_type.clone().into())),
+            None => emoji.clone(),
+        }
+    }
+}
+
+/// How the hook suggests emojis from the staged changes, listing them first.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Suggestions {
+    /// Whether to apply the built-in rules after the `rules` here. Defaults
+    /// to `true`.
+    pub builtin: Option<bool>,
+    pub rules: Option<Vec<suggest::Rule>>,
+}
+
+/// An emoji of the project's own, e.g. `🦀 :crab: Rust-specific change.`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExtraEmoji {
+    pub emoji: String,
+    /// The colons are optional.
+    pub code: String,
+    pub description: String,
+    /// How a change marked with it bumps the version, if at all.
+    #[serde(default, deserialize_with = "parsed")]
+    pub semver: Option<SemverBump>,
+    /// The Conventional Commits type of changes marked with it, if any.
+    #[serde(default, rename = "type", deserialize_with = "commit_type")]
+    pub commit_type: Option<String>,
+    /// What the picker groups it under, e.g. `infra`, if anything.
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Hook {
+    /// Whether `--hook` does anything. Defaults to `true`; a repository can
+    /// turn the hook off without uninstalling it everywhere.
+    pub enabled: Option<bool>,
+    /// Whether to offer replacing the emoji a message already starts with,
+    /// e.g. when amending, rather than leaving it be. Defaults to `false`.
+    pub replace: Option<bool>,
+    /// The emoji `auto` applies when the subject hints at none, by glyph,
+    /// `:code:` or name.
+    pub default_emoji: Option<String>,
+    /// Whether to show the commit being prepared below the picker.
+    /// Defaults to `true`.
+    pub preview: Option<bool>,
+    pub sources: Sources,
+}
+
+impl Hook {
+    /// What to do for a commit whose message comes from `source`, as git
+    /// passes it to `prepare-commit-msg`.
+    pub fn behaviour(&self, source: Option<&str>) -> Behaviour {
+        let sources = &self.sources;
+        let (configured, default) = match source {
+            Some("message") => (sources.message, Behaviour::Auto),
+            Some("template") => (sources.template, Behaviour::Prompt),
+            Some("merge") => (sources.merge, Behaviour::Skip),
+            Some("squash") => (sources.squash, Behaviour::Skip),
+            Some("commit") => (sources.commit, Behaviour::Prompt),
+            // A plain `git commit`, or a source newer than we know of.
+            _ => (sources.none, Behaviour::Prompt),
+        };
+
+        configured.unwrap_or(default)
+    }
+}
+
+/// What the hook does for each source of the commit message git reports.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Sources {
+    /// `-m` or `-F`. Defaults to `auto`.
+    pub message: Option<Behaviour>,
+    /// `-t` or `commit.template`. Defaults to `prompt`.
+    pub template: Option<Behaviour>,
+    /// A merge, or `.git/MERGE_MSG` being around. Defaults to `skip`.
+    pub merge: Option<Behaviour>,
+    /// `.git/SQUASH_MSG` being around. Defaults to `skip`.
+    pub squash: Option<Behaviour>,
+    /// `-c`, `-C` or `--amend`. Defaults to `prompt`.
+    pub commit: Option<Behaviour>,
+    /// None of the above, i.e. a plain `git commit`. Defaults to `prompt`.
+    pub none: Option<Behaviour>,
+}
+
+/// What the hook does for a commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Behaviour {
+    /// Leave the message alone.
+    Skip,
+    /// Show the picker.
+    Prompt,
+    /// Apply the first emoji the subject's words hint at without asking,
+    /// e.g. 🐛 for "Fix crash", or failing that the `default-emoji`, or
+    /// failing that leave the message alone.
+    Auto,
+}
+
+impl Config {
+    /// The user's config overlaid with the current repository's. Missing
+    /// files are fine; unreadable or invalid ones are errors.
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let mut config = match user_path() {
+            Some(path) => Self::read(&path)?.unwrap_or_default(),
+            None => Self::default(),
+        };
+        // Outside a repository there's simply no repository config.
+        if let Ok(toplevel) = git::toplevel() {
+            if let Some(repo) = Self::read(&toplevel.join(REPO_FILE))? {
+                config = config.overlay(repo);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Parse the config file at `path`, or `None` if there is none.
+    fn read(path: &Path) -> Result<Option<Self>, Box<dyn Error>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read `{}`: {e}", path.display()).into()),
+        };
+
+        toml::from_str(&text)
+            .map(Some)
+            .map_err(|e| format!("Invalid config `{}`: {e}", path.display()).into())
+    }
+
+    /// `self` with every setting `over` makes replaced by its value. Lists
+    /// and tables are replaced whole rather than merged.
+    fn overlay(self, over: Self) -> Self {
+        Self {
+            color_scheme: over.color_scheme.or(self.color_scheme),
+            prefix: over.prefix.or(self.prefix),
+            group_by_category: over.group_by_category.or(self.group_by_category),
+            keybindings: Keybindings {
+                pick: over.keybindings.pick.or(self.keybindings.pick),
+                cancel: over.keybindings.cancel.or(self.keybindings.cancel),
+                clear: over.keybindings.clear.or(self.keybindings.clear),
+                move_up: over.keybindings.move_up.or(self.keybindings.move_up),
+                move_down: over.keybindings.move_down.or(self.keybindings.move_down),
+                toggle: over.keybindings.toggle.or(self.keybindings.toggle),
+            },
+            prompts: Prompts {
+                scope: over.prompts.scope.or(self.prompts.scope),
+                summary: over.prompts.summary.or(self.prompts.summary),
+            },
+            emojis: Emojis {
+                builtin: over.emojis.builtin.or(self.emojis.builtin),
+                extra: over.emojis.extra.or(self.emojis.extra),
+                disabled: over.emojis.disabled.or(self.emojis.disabled),
+                types: over.emojis.types.or(self.emojis.types),
+            },
+            suggestions: Suggestions {
+                builtin: over.suggestions.builtin.or(self.suggestions.builtin),
+                rules: over.suggestions.rules.or(self.suggestions.rules),
+            },
+            hook: Hook {
+                enabled: over.hook.enabled.or(self.hook.enabled),
+                replace: over.hook.replace.or(self.hook.replace),
+                default_emoji: over.hook.default_emoji.or(self.hook.default_emoji),
+                preview: over.hook.preview.or(self.hook.preview),
+                sources: Sources {
+                    message: over.hook.sources.message.or(self.hook.sources.message),
+                    template: over.hook.sources.template.or(self.hook.sources.template),
+                    merge: over.hook.sources.merge.or(self.hook.sources.merge),
+                    squash: over.hook.sources.squash.or(self.hook.sources.squash),
+                    commit: over.hook.sources.commit.or(self.hook.sources.commit),
+                    none: over.hook.sources.none.or(self.hook.sources.none),
+                },
+            },
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/gimoji`, falling back to `~/.config`.
+pub fn user_dir() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| Path::new(dir).is_absolute())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+    Some(config_dir.join("gimoji"))
+}
+
+/// `config.toml` in the [`user_dir`].
+fn user_path() -> Option<PathBuf> {
+    user_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Whether `query` names `emoji` exactly, by glyph (trailing variation
+/// selectors are optional), `:code:` or name, the latter two ignoring case.
+pub fn names(query: &str, emoji: &Emoji) -> bool {
+    fn glyph(s: &str) -> &str {
+        s.trim_end_matches(['\u{fe0e}', '\u{fe0f}'])
+    }
+    let query = query.trim();
+
+    glyph(query) == glyph(emoji.emoji())
+        || query.eq_ignore_ascii_case(emoji.code())
+        || query.eq_ignore_ascii_case(emoji.name())
+}
+
+/// Deserialize a string into anything that parses from one, with the parse
+/// error as the message.
+fn parsed<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    let text = String::deserialize(deserializer)?;
+
+    text.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+/// The default prefix when asking for a scope, which `{emoji} ` has no place
+/// for.
+const SCOPED_PREFIX: &str = "{type}({scope}): {emoji} ";
+
+/// Deserialize a Conventional Commits type, which is a lowercase word: a
+/// breaking change's `!` goes after the scope, not in the type.
+fn commit_type<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let commit_type = String::deserialize(deserializer)?;
+    check_commit_type(&commit_type).map_err(serde::de::Error::custom)?;
+
+    Ok(Some(commit_type))
+}
+
+/// Deserialize a table of Conventional Commits types, like [`commit_type`]
+/// does each of them.
+fn commit_types<'de, D>(deserializer: D) -> Result<Option<BTreeMap<String, String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let types = BTreeMap::<String, String>::deserialize(deserializer)?;
+    for commit_type in types.values() {
+        check_commit_type(commit_type).map_err(serde::de::Error::custom)?;
+    }
+
+    Ok(Some(types))
+}
+
+fn check_commit_type(commit_type: &str) -> Result<(), String> {
+    let valid = commit_type.starts_with(|c: char| c.is_ascii_lowercase())
+        && commit_type
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err(format!(
+            "invalid commit type `{commit_type}` (expected lowercase letters, digits and `-`, \
+             e.g. `fix` or `build-deps`)"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Name of the repository config, at the root of the working tree.
+const REPO_FILE: &str = ".gimoji.toml";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Config {
+        toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn asking_for_a_scope_gives_it_a_place_by_default() {
+        let bug = gimoji_core::lookup("bug").unwrap();
+        let prefix = |text| {
+            parse(text)
+                .prompts
+                .default_prefix()
+                .render(bug, Some("core"))
+        };
+        assert_eq!(prefix(""), "🐛 ");
+        assert_eq!(prefix("[prompts]\nscope = true"), "fix(core): 🐛 ");
+        // Without a scope typed in, the parentheses go.
+        let scoped = parse("[prompts]\nscope = true").prompts.default_prefix();
+        assert_eq!(scoped.render(bug, None), "fix: 🐛 ");
+    }
+
+    #[test]
+    fn every_setting_parses() {
+        let config = parse(
+            r#"
+            color-scheme = "light"
+            prefix = "{emoji}  "
+            group-by-category = true
+
+            [keybindings]
+            move-up = ["ctrl-p", "up"]
+            pick = ["enter", "tab"]
+
+            [emojis]
+            builtin = true
+            disabled = ["💩", ":beers:"]
+
+            [[emojis.extra]]
+            emoji = "🦀"
+            code = "crab"
+            description = "Rust-specific change."
+            semver = "patch"
+            category = "tooling"
+
+            [prompts]
+            scope = true
+
+            [hook]
+            enabled = false
+            replace = true
+            "#,
+        );
+
+        assert!(matches!(config.color_scheme, Some(ColorScheme::Light)));
+        assert_eq!(config.prefix, "{emoji}  ".parse().ok());
+        assert_eq!(config.group_by_category, Some(true));
+        assert_eq!(config.keybindings.move_up.map(|keys| keys.len()), Some(2));
+        assert_eq!(config.keybindings.pick.map(|keys| keys.len()), Some(2));
+        assert!(config.keybindings.cancel.is_none());
+        assert_eq!(config.prompts.scope, Some(true));
+        assert_eq!(config.prompts.summary, None);
+        let extra = config.emojis.extra_emojis();
+        assert_eq!(extra[0].code(), ":crab:");
+        assert_eq!(extra[0].semver(), Some(SemverBump::Patch));
+        assert_eq!(extra[0].category(), Some("tooling"));
+        assert_eq!(config.emojis.builtin, Some(true));
+        assert_eq!(config.emojis.disabled.unwrap(), ["💩", ":beers:"]);
+        assert_eq!(config.hook.enabled, Some(false));
+        assert_eq!(config.hook.replace, Some(true));
+    }
+
+    #[test]
+    fn mistakes_are_reported() {
+        let err = |text: &str| toml::from_str::<Config>(text).unwrap_err().to_string();
+        assert!(err("colour-scheme = \"dark\"").contains("unknown field"));
+        assert!(err("[keybindings]\npick = [\"hyper-k\"]").contains("unknown key `hyper-k`"));
+        assert!(err("color-scheme = \"sepia\"").contains("unknown variant"));
+        assert!(err("prefix = \"{kind} \"").contains("unknown placeholder `{kind}`"));
+        let extra = "[[emojis.extra]]\nemoji = \"🦀\"\ncode = \"crab\"\ndescription = \"\"\n";
+        assert!(err(&format!("{extra}semver = \"huge\"")).contains("unknown semver bump"));
+        assert!(err(extra.replace("emoji =", "glyph =").as_str()).contains("unknown field"));
+        // A breaking change's `!` has no place in the type itself.
+        let types = "[emojis.types]\nrecycle = \"refactor!\"";
+        assert!(err(types).contains("invalid commit type `refactor!`"));
+        assert!(err(&format!("{extra}type = \"Fix\"")).contains("invalid commit type `Fix`"));
+    }
+
+    #[test]
+    fn repository_settings_take_precedence() {
+        let user = parse(
+            r#"
+            color-scheme = "dark"
+            prefix = "{code} "
+            [hook]
+            enabled = false
+            [emojis]
+            disabled = ["💩"]
+            "#,
+        );
+        let repo = parse(
+            r#"
+            color-scheme = "light"
+            [emojis]
+            disabled = ["🍻"]
+            "#,
+        );
+        let config = user.overlay(repo);
+
+        assert!(matches!(config.color_scheme, Some(ColorScheme::Light)));
+        assert_eq!(config.prefix, "{code} ".parse().ok());
+        assert_eq!(config.hook.enabled, Some(false));
+        assert_eq!(config.emojis.disabled.unwrap(), ["🍻"]);
+    }
+
+    #[test]
+    fn hook_behaviour_follows_the_message_source() {
+        let defaults = Hook::default();
+        assert_eq!(defaults.behaviour(None), Behaviour::Prompt);
+        assert_eq!(defaults.behaviour(Some("message")), Behaviour::Auto);
+        assert_eq!(defaults.behaviour(Some("template")), Behaviour::Prompt);
+        assert_eq!(defaults.behaviour(Some("merge")), Behaviour::Skip);
+        assert_eq!(defaults.behaviour(Some("squash")), Behaviour::Skip);
+        assert_eq!(defaults.behaviour(Some("commit")), Behaviour::Prompt);
+        assert_eq!(defaults.behaviour(Some("rebase")), Behaviour::Prompt);
+
+        let user = parse("[hook.sources]\nmerge = \"prompt\"\ncommit = \"skip\"");
+        let repo = parse("[hook]\ndefault-emoji = \"sparkles\"\n[hook.sources]\ncommit = \"auto\"");
+        let hook = user.overlay(repo).hook;
+        assert_eq!(hook.behaviour(Some("merge")), Behaviour::Prompt);
+        assert_eq!(hook.behaviour(Some("commit")), Behaviour::Auto);
+        assert_eq!(hook.behaviour(Some("squash")), Behaviour::Skip);
+        assert_eq!(hook.default_emoji.as_deref(), Some("sparkles"));
+    }
+
+    #[test]
+    fn extra_emojis_join_or_replace_the_database() {
+        let config = parse(
+            r#"
+            [emojis]
+            disabled = ["⚡", ":sparkles:", "Memo"]
+
+            [[emojis.extra]]
+            emoji = "🦀"
+            code = "crab"
+            description = "Rust-specific change."
+
+            [[emojis.extra]]
+            emoji = "🪲"
+            code = ":bug:"
+            description = "Squash a bug."
+            "#,
+        );
+        let emojis = config.emojis.picker_emojis();
+        let find = |code| emojis.iter().find(|e| e.code() == code);
+
+        assert_eq!(find(":crab:").unwrap().emoji(), "🦀");
+        assert_eq!(find(":bug:").unwrap().emoji(), "🪲");
+        assert_eq!(emojis.iter().filter(|e| e.code() == ":bug:").count(), 1);
+        for disabled in [":zap:", ":sparkles:", ":memo:"] {
+            assert!(find(disabled).is_none(), "{disabled} is disabled");
+        }
+        assert_eq!(emojis.len(), EMOJIS.len() + 1 - 3);
+    }
+
+    #[test]
+    fn commit_types_can_be_overridden() {
+        let config = parse(
+            r#"
+            [emojis.types]
+            ":recycle:" = "perf"
+            art = "refactor"
+
+            [[emojis.extra]]
+            emoji = "🦀"
+            code = "crab"
+            description = "Rust-specific change."
+            type = "chore"
+            "#,
+        );
+        let emojis = config.emojis.picker_emojis();
+        let find = |code| emojis.iter().find(|e| e.code() == code).unwrap();
+
+        assert_eq!(find(":art:").commit_type(), Some("refactor"));
+        assert_eq!(find(":recycle:").commit_type(), Some("perf"));
+        assert_eq!(find(":crab:").commit_type(), Some("chore"));
+        assert_eq!(find(":bug:").commit_type(), Some("fix"));
+        let art = gimoji_core::lookup("art").unwrap();
+        assert_eq!(config.emojis.typed(art).commit_type(), Some("refactor"));
+    }
+
+    #[test]
+    fn builtin_emojis_can_be_turned_off() {
+        let config = parse(
+            r#"
+            [emojis]
+            builtin = false
+
+            [[emojis.extra]]
+            emoji = "🔐"
+            code = "auth"
+            description = "Authentication."
+            "#,
+        );
+        let emojis = config.emojis.picker_emojis();
+        assert_eq!(emojis.len(), 1);
+        assert_eq!(emojis[0].code(), ":auth:");
+        assert!(matches!(
+            Emojis::default().picker_emojis(),
+            Cow::Borrowed(emojis) if emojis.len() == EMOJIS.len()
+        ));
+    }
+
+    #[test]
+    fn missing_files_are_no_config() {
+        let path = std::env::temp_dir().join("gimoji-no-such-config.toml");
+        assert!(Config::read(&path).unwrap().is_none());
+    }
+}
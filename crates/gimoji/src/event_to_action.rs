@@ -1,31 +1,221 @@
+use std::{fmt, str::FromStr};
+
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use gimoji_core::Action;
+use serde::Deserialize;
+
+use crate::config;
 
 #[derive(Debug)]
 pub struct ExitSignal;
 
+/// The action for `event`. While `typing` the details after a pick, or
+/// into a search that isn't empty, a toggle key that types text, like
+/// space, types instead.
 pub fn from_key_event(
     event: KeyEvent,
     search_is_empty: bool,
+    typing: bool,
+    keymap: &Keymap,
 ) -> Result<Option<Action>, ExitSignal> {
-    match event.code {
-        KeyCode::Char(c) if c == 'c' && event.modifiers.contains(KeyModifiers::CONTROL) => {
-            Err(ExitSignal)
+    if event.code == KeyCode::Char('c') && event.modifiers.contains(KeyModifiers::CONTROL) {
+        return Err(ExitSignal);
+    }
+    match keymap.binding(event) {
+        Some(Binding::Toggle) if types_text(event) && (typing || !search_is_empty) => {}
+        Some(binding) => {
+            return Ok(Some(match binding {
+                Binding::Pick => Action::PickFocused,
+                Binding::Cancel if search_is_empty => Action::Cancel,
+                Binding::Cancel | Binding::Clear => Action::ClearSearch,
+                Binding::MoveUp => Action::MoveUp,
+                Binding::MoveDown => Action::MoveDown,
+                Binding::Toggle => Action::Toggle,
+            }));
         }
-        KeyCode::Enter => Ok(Some(Action::PickFocused)),
-        KeyCode::Esc => Ok(Some(if search_is_empty {
-            Action::Cancel
-        } else {
-            Action::ClearSearch
-        })),
-        KeyCode::Down => Ok(Some(Action::MoveDown)),
-        KeyCode::Up => Ok(Some(Action::MoveUp)),
+        None => {}
+    }
+
+    match event.code {
         KeyCode::Backspace => Ok(Some(Action::Backspace)),
         KeyCode::Char(c) => Ok(Some(Action::Append(c))),
         _ => Ok(None),
     }
 }
 
+/// Whether `event` types a character into the search, rather than being
+/// a shortcut.
+fn types_text(event: KeyEvent) -> bool {
+    matches!(event.code, KeyCode::Char(_)) && (event.modifiers - KeyModifiers::SHIFT).is_empty()
+}
+
+/// Which keys do what in the picker, on top of typing into the search.
+#[derive(Debug)]
+pub struct Keymap {
+    bindings: Vec<(Key, Binding)>,
+}
+
+impl Keymap {
+    /// The default keys, with those of each action the config lists
+    /// replaced by its own.
+    pub fn new(config: &config::Keybindings) -> Self {
+        let actions = [
+            (
+                Binding::Pick,
+                &config.pick,
+                &[Key::plain(KeyCode::Enter)][..],
+            ),
+            (Binding::Cancel, &config.cancel, &[Key::plain(KeyCode::Esc)]),
+            (Binding::Clear, &config.clear, &[]),
+            (Binding::MoveUp, &config.move_up, &[Key::plain(KeyCode::Up)]),
+            (
+                Binding::MoveDown,
+                &config.move_down,
+                &[Key::plain(KeyCode::Down)],
+            ),
+            (Binding::Toggle, &config.toggle, &[Key::plain(KeyCode::Tab)]),
+        ];
+        let bindings = actions
+            .into_iter()
+            .flat_map(|(binding, configured, default)| {
+                configured
+                    .as_deref()
+                    .unwrap_or(default)
+                    .iter()
+                    .map(move |key| (*key, binding))
+            })
+            .collect();
+
+        Self { bindings }
+    }
+
+    fn binding(&self, event: KeyEvent) -> Option<Binding> {
+        self.bindings
+            .iter()
+            .find(|(key, _)| key.matches(event))
+            .map(|(_, binding)| *binding)
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&config::Keybindings::default())
+    }
+}
+
+/// The picker actions keys can be bound to.
+#[derive(Debug, Clone, Copy)]
+enum Binding {
+    Pick,
+    /// Clear the search or, if it's already empty, quit.
+    Cancel,
+    Clear,
+    MoveUp,
+    MoveDown,
+    /// Toggle the selected emoji for picking several.
+    Toggle,
+}
+
+/// A key with its modifiers, written like `enter`, `ctrl-p` or `alt-j`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    const fn plain(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn matches(&self, event: KeyEvent) -> bool {
+        // Shift is part of the character itself, e.g. `K` rather than `k`.
+        let modifiers = match event.code {
+            KeyCode::Char(_) => event.modifiers - KeyModifiers::SHIFT,
+            _ => event.modifiers,
+        };
+
+        self.code == event.code && self.modifiers == modifiers
+    }
+}
+
+impl FromStr for Key {
+    type Err = UnknownKey;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || UnknownKey(s.to_string());
+        let mut parts: Vec<&str> = s.split('-').collect();
+        // A trailing `-` is the key itself, e.g. `ctrl--`.
+        if parts.len() > 1 && parts[parts.len() - 1].is_empty() {
+            parts.pop();
+            *parts.last_mut().ok_or_else(unknown)? = "-";
+        }
+        let (name, modifier_names) = parts.split_last().ok_or_else(unknown)?;
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in modifier_names {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(unknown()),
+            };
+        }
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_ascii_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                f => match f.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(unknown()),
+                },
+            },
+        };
+
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = UnknownKey;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// A key name [`Key`] couldn't make sense of.
+#[derive(Debug)]
+pub struct UnknownKey(String);
+
+impl fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown key `{}` (expected e.g. `enter`, `ctrl-p` or `alt-j`)",
+            self.0
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,10 +224,14 @@ mod tests {
         KeyEvent::new(code, KeyModifiers::empty())
     }
 
+    fn action(event: KeyEvent, keymap: &Keymap) -> Option<Action> {
+        from_key_event(event, true, false, keymap).unwrap()
+    }
+
     #[test]
     fn enter_maps_to_pick_focused() {
         assert_eq!(
-            from_key_event(key(KeyCode::Enter), true).unwrap(),
+            from_key_event(key(KeyCode::Enter), true, false, &Keymap::default()).unwrap(),
             Some(Action::PickFocused)
         );
     }
@@ -45,7 +239,7 @@ mod tests {
     #[test]
     fn esc_on_empty_search_is_cancel() {
         assert_eq!(
-            from_key_event(key(KeyCode::Esc), true).unwrap(),
+            from_key_event(key(KeyCode::Esc), true, false, &Keymap::default()).unwrap(),
             Some(Action::Cancel)
         );
     }
@@ -53,7 +247,7 @@ mod tests {
     #[test]
     fn esc_with_search_clears() {
         assert_eq!(
-            from_key_event(key(KeyCode::Esc), false).unwrap(),
+            from_key_event(key(KeyCode::Esc), false, false, &Keymap::default()).unwrap(),
             Some(Action::ClearSearch)
         );
     }
@@ -61,14 +255,100 @@ mod tests {
     #[test]
     fn ctrl_c_signals_exit() {
         let ev = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
-        assert!(matches!(from_key_event(ev, true), Err(ExitSignal)));
+        assert!(matches!(
+            from_key_event(ev, true, false, &Keymap::default()),
+            Err(ExitSignal)
+        ));
     }
 
     #[test]
     fn plain_letter_appends() {
         assert_eq!(
-            from_key_event(key(KeyCode::Char('a')), true).unwrap(),
+            from_key_event(key(KeyCode::Char('a')), true, false, &Keymap::default()).unwrap(),
             Some(Action::Append('a'))
         );
     }
+
+    /// The search typing `keys` gives, one after the other.
+    fn typed(keys: &str, keymap: &Keymap) -> String {
+        let mut search = String::new();
+        for c in keys.chars() {
+            let event = key(KeyCode::Char(c));
+            match from_key_event(event, search.is_empty(), false, keymap).unwrap() {
+                Some(Action::Append(c)) => search.push(c),
+                action => panic!("{c:?} did {action:?}"),
+            }
+        }
+
+        search
+    }
+
+    #[test]
+    fn tab_toggles_and_space_types() {
+        let keymap = Keymap::default();
+        assert_eq!(action(key(KeyCode::Tab), &keymap), Some(Action::Toggle));
+        assert_eq!(typed("fix a", &keymap), "fix a");
+    }
+
+    #[test]
+    fn a_toggle_key_that_types_only_toggles_before_the_search() {
+        let keymap = Keymap::new(&config::Keybindings {
+            toggle: Some(vec!["space".parse().unwrap()]),
+            ..Default::default()
+        });
+        let space = key(KeyCode::Char(' '));
+        assert_eq!(action(space, &keymap), Some(Action::Toggle));
+        assert_eq!(typed("fix a", &keymap), "fix a");
+        // Nor while typing the details.
+        assert_eq!(
+            from_key_event(space, true, true, &keymap).unwrap(),
+            Some(Action::Append(' '))
+        );
+    }
+
+    #[test]
+    fn configured_keys_replace_an_actions_defaults() {
+        let keymap = Keymap::new(&config::Keybindings {
+            move_up: Some(vec!["ctrl-p".parse().unwrap(), "up".parse().unwrap()]),
+            move_down: Some(vec!["ctrl-n".parse().unwrap()]),
+            ..Default::default()
+        });
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+        assert_eq!(action(ctrl('p'), &keymap), Some(Action::MoveUp));
+        assert_eq!(action(key(KeyCode::Up), &keymap), Some(Action::MoveUp));
+        assert_eq!(action(ctrl('n'), &keymap), Some(Action::MoveDown));
+        // The default is gone, and other actions keep theirs.
+        assert_eq!(action(key(KeyCode::Down), &keymap), None);
+        assert_eq!(
+            action(key(KeyCode::Enter), &keymap),
+            Some(Action::PickFocused)
+        );
+        // Without the modifier it's still typing.
+        assert_eq!(
+            action(key(KeyCode::Char('p')), &keymap),
+            Some(Action::Append('p'))
+        );
+    }
+
+    #[test]
+    fn keys_parse_with_modifiers() {
+        let parse = |s: &str| s.parse::<Key>().map(|k| (k.code, k.modifiers)).ok();
+        assert_eq!(parse("enter"), Some((KeyCode::Enter, KeyModifiers::NONE)));
+        assert_eq!(
+            parse("Ctrl-Alt-k"),
+            Some((
+                KeyCode::Char('k'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ))
+        );
+        assert_eq!(
+            parse("ctrl--"),
+            Some((KeyCode::Char('-'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(parse("f5"), Some((KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(parse("hyper-k"), None);
+        assert_eq!(parse("f13"), None);
+        assert_eq!(parse(""), None);
+    }
 }
@@ -0,0 +1,153 @@
+use std::{error::Error, path::PathBuf, process::Command};
+
+use crate::suggest::{Change, Status};
+
+/// The most recent tag reachable from `HEAD`, or `None` when none (matching
+/// `pattern`, if given) is: the repository has no tags, or only on other
+/// branches.
+///
+/// `pattern` is a glob in the sense of `git describe --match`, e.g.
+/// `gimoji-core-*` to pick one crate's tags out of a workspace.
+pub fn last_tag(pattern: Option<&str>) -> Result<Option<String>, Box<dyn Error>> {
+    let mut list = vec!["tag", "--merged", "HEAD", "--list"];
+    list.extend(pattern);
+    if run(&list)?.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let mut describe = vec!["describe", "--tags", "--abbrev=0"];
+    if let Some(pattern) = pattern {
+        describe.extend(["--match", pattern]);
+    }
+
+    run(&describe).map(|tag| Some(tag.trim().to_string()))
+}
+
+/// Subject lines of the non-merge commits in `range`, newest first.
+pub fn subjects(range: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let log = run(&["log", "--no-merges", "--format=%s", range, "--"])?;
+
+    Ok(log.lines().map(str::to_string).collect())
+}
+
+/// Abbreviated hashes and subject lines of the non-merge commits in
+/// `range`, newest first.
+pub fn commits(range: &str) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let log = run(&["log", "--no-merges", "--format=%h %s", range, "--"])?;
+
+    Ok(log
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(hash, subject)| (hash.to_string(), subject.to_string()))
+        .collect())
+}
+
+/// Committer date (`YYYY-MM-DD`) of the newest commit in `range`, or `None`
+/// if the range is empty.
+pub fn last_commit_date(range: &str) -> Result<Option<String>, Box<dyn Error>> {
+    let date = run(&["log", "-1", "--format=%cs", range, "--"])?;
+    let date = date.trim();
+
+    Ok((!date.is_empty()).then(|| date.to_string()))
+}
+
+/// What commit message lines start with to be comments: `core.commentString`
+/// or `core.commentChar`, `#` by default. `auto` counts as the default too,
+/// since git only picks the actual character after the hook has run.
+pub fn comment_string() -> String {
+    ["core.commentString", "core.commentChar"]
+        .into_iter()
+        // Unset keys make `git config` fail.
+        .find_map(|key| run(&["config", "--get", key]).ok())
+        .map(|value| value.trim_end_matches('\n').to_string())
+        .filter(|value| !value.is_empty() && value != "auto")
+        .unwrap_or_else(|| "#".to_string())
+}
+
+/// The changes staged for the next commit.
+pub fn staged_changes() -> Result<Vec<Change>, Box<dyn Error>> {
+    let diff = run(&["diff", "--cached", "--name-status", "-z"])?;
+    let mut fields = diff.split_terminator('\0');
+    let mut changes = Vec::new();
+    while let (Some(status), Some(mut path)) = (fields.next(), fields.next()) {
+        let status = status.chars().next().and_then(Status::from_letter);
+        if let Some(Status::Renamed | Status::Copied) = status {
+            // Followed by where the file went.
+            path = fields.next().unwrap_or(path);
+        }
+        if let Some(status) = status {
+            changes.push(Change {
+                status,
+                path: path.to_string(),
+            });
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Whether a rebase, cherry-pick or revert is under way, replaying commits
+/// whose messages were written already.
+pub fn sequencer_in_progress() -> Result<bool, Box<dyn Error>> {
+    let git_dir = run(&["rev-parse", "--absolute-git-dir"])?;
+    let git_dir = PathBuf::from(git_dir.trim_end_matches('\n'));
+
+    Ok(SEQUENCER_STATE
+        .iter()
+        .any(|name| git_dir.join(name).exists()))
+}
+
+/// Root directory of the working tree we're in.
+pub fn toplevel() -> Result<PathBuf, Box<dyn Error>> {
+    run(&["rev-parse", "--show-toplevel"]).map(|dir| PathBuf::from(dir.trim_end_matches('\n')))
+}
+
+/// The directory git runs the hooks of the repository we're in from: its
+/// `core.hooksPath`, or else the `hooks` directory shared by all its
+/// worktrees.
+pub fn hooks_dir() -> Result<PathBuf, Box<dyn Error>> {
+    run(&["rev-parse", "--path-format=absolute", "--git-path", "hooks"])
+        .map(|dir| PathBuf::from(dir.trim_end_matches('\n')))
+}
+
+/// The user's global `core.hooksPath`, as they wrote it, if set.
+pub fn global_hooks_path() -> Option<String> {
+    run(&["config", "--global", "--get", "core.hooksPath"])
+        .ok()
+        .map(|path| path.trim_end_matches('\n').to_string())
+        .filter(|path| !path.is_empty())
+}
+
+/// Set the user's global `core.hooksPath`, or unset it.
+pub fn set_global_hooks_path(path: Option<&str>) -> Result<(), Box<dyn Error>> {
+    match path {
+        Some(path) => run(&["config", "--global", "core.hooksPath", path]),
+        None => run(&["config", "--global", "--unset", "core.hooksPath"]),
+    }
+    .map(drop)
+}
+
+fn run(args: &[&str]) -> Result<String, Box<dyn Error>> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("`git {}` failed: {}", args.join(" "), stderr.trim()).into());
+    }
+
+    String::from_utf8(output.stdout).map_err(Into::into)
+}
+
+/// What git keeps in the repository's directory while it replays commits:
+/// the state of `git rebase` (`-merge` for the default backend, `-apply`
+/// for the other), and that of `git cherry-pick` and `git revert`, for a
+/// single commit or a sequence of them.
+const SEQUENCER_STATE: [&str; 5] = [
+    "rebase-merge",
+    "rebase-apply",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+    "sequencer",
+];
@@ -0,0 +1,77 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use gimoji_core::{history, History, HistoryEntry};
+
+use crate::message;
+
+/// The picker's history of picked emojis, kept in a file under the user's
+/// state directory: `$XDG_STATE_HOME/gimoji/history`, or
+/// `~/.local/state/gimoji/history` when that isn't set.
+pub struct FileHistory {
+    path: PathBuf,
+}
+
+impl FileHistory {
+    /// The history at its default location, or `None` when there is no
+    /// home directory to put it in.
+    pub fn new() -> Option<Self> {
+        let state_dir = std::env::var_os("XDG_STATE_HOME")
+            .filter(|dir| Path::new(dir).is_absolute())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/state"))
+            })?;
+
+        Some(Self::at(state_dir.join("gimoji").join("history")))
+    }
+
+    fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl History for FileHistory {
+    type Error = io::Error;
+
+    fn load(&mut self) -> Result<Vec<HistoryEntry>, Self::Error> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => Ok(history::parse(&text)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&mut self, entries: &[HistoryEntry]) -> Result<(), Self::Error> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Hooks can run at once, and either be interrupted.
+        message::write_atomically(&self.path, &history::format(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_survives_a_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("gimoji-history-{}", std::process::id()));
+        let mut file = FileHistory::at(dir.join("gimoji").join("history"));
+        assert_eq!(file.load().unwrap(), []);
+
+        let mut entries = Vec::new();
+        history::record(&mut entries, "🐛");
+        history::record(&mut entries, "✨");
+        file.save(&entries).unwrap();
+        assert_eq!(file.load().unwrap(), entries);
+        // Nothing of the write is left behind but the file.
+        assert_eq!(fs::read_dir(dir.join("gimoji")).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,431 @@
+use std::{
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+};
+#[cfg(unix)]
+use std::{fs::Permissions, os::unix::prelude::PermissionsExt};
+
+use crate::{config, git};
+
+/// A git hook that runs gimoji.
+pub struct Hook {
+    /// The hook's file name, e.g. `prepare-commit-msg`.
+    name: &'static str,
+    /// What the hook script runs.
+    command: &'static str,
+}
+
+/// Prompts for an emoji as the commit message is prepared.
+pub const PREPARE_COMMIT_MSG: Hook = Hook {
+    name: "prepare-commit-msg",
+    command: "gimoji --hook \"$1\" \"$2\"",
+};
+
+/// Rejects commit messages that don't start with an emoji.
+pub const COMMIT_MSG: Hook = Hook {
+    name: "commit-msg",
+    command: "gimoji lint \"$1\"",
+};
+
+/// All the hooks gimoji can install.
+pub const ALL: [&Hook; 2] = [&PREPARE_COMMIT_MSG, &COMMIT_MSG];
+
+impl Hook {
+    /// Add the hook to the repository we're in, in the hooks directory git
+    /// actually uses (e.g. `core.hooksPath`). A script that's already there
+    /// gets gimoji's command appended, between marker comments, if it's a sh
+    /// or bash one; for any other, the user is told what to add by hand.
+    ///
+    /// Does nothing if gimoji is already in there.
+    pub fn install(&self) -> Result<(), Box<dyn Error>> {
+        let path = self.path()?;
+        // A blank script might as well not be there.
+        let existing = read(&path)?.filter(|script| !script.trim().is_empty());
+        let script = match existing {
+            Some(script) if self.is_in(&script) => {
+                println!("gimoji is already in `{}`", path.display());
+
+                return Ok(());
+            }
+            _ if is_global(&path) => {
+                return Err(format!(
+                    "git runs gimoji's global hooks here; \
+                    run `gimoji --uninstall --global` first to install `{}` per repository",
+                    self.name,
+                )
+                .into());
+            }
+            Some(script) if !is_shell(&script) => {
+                eprintln!(
+                    "Add this to `{}` by hand, to run gimoji:\n\n    {}\n",
+                    path.display(),
+                    self.command,
+                );
+
+                return Err(format!(
+                    "`{}` isn't a sh or bash script, so gimoji can't add itself to it",
+                    path.display(),
+                )
+                .into());
+            }
+            Some(mut script) => {
+                if !script.ends_with('\n') {
+                    script.push('\n');
+                }
+                script.push_str(&self.block());
+                println!(
+                    "Added gimoji to the end of the existing `{}`; make sure the script gets there",
+                    path.display(),
+                );
+
+                script
+            }
+            None => {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                println!("Installed `{}`", path.display());
+
+                format!("{HEADER}{}", self.block())
+            }
+        };
+        fs::write(&path, script)?;
+        #[cfg(unix)]
+        {
+            let mode = fs::metadata(&path)?.permissions().mode();
+            fs::set_permissions(&path, Permissions::from_mode(mode | 0o744))?;
+        }
+
+        Ok(())
+    }
+
+    /// Take gimoji back out of the hook, removing the script altogether if
+    /// nothing else is left in it. Returns whether gimoji was in there.
+    pub fn uninstall(&self) -> Result<bool, Box<dyn Error>> {
+        let path = self.path()?;
+        if is_global(&path) {
+            // `--uninstall --global` takes care of those.
+            return Ok(false);
+        }
+        let Some(script) = read(&path)?.filter(|script| self.is_in(script)) else {
+            return Ok(false);
+        };
+        let rest = self.without(&script);
+        if rest.strip_prefix(HEADER).unwrap_or(&rest).trim().is_empty() {
+            fs::remove_file(&path)?;
+            println!("Removed `{}`", path.display());
+        } else {
+            fs::write(&path, rest)?;
+            println!("Removed gimoji from `{}`", path.display());
+        }
+
+        Ok(true)
+    }
+
+    /// Where the hook goes, and whether gimoji is in there.
+    pub fn status(&self) -> Result<(PathBuf, bool), Box<dyn Error>> {
+        let path = self.path()?;
+        let installed = read(&path)?.is_some_and(|script| self.is_in(&script));
+
+        Ok((path, installed))
+    }
+
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    fn path(&self) -> Result<PathBuf, Box<dyn Error>> {
+        Ok(git::hooks_dir()?.join(self.name))
+    }
+
+    /// gimoji's part of the script, marked so it can be found again.
+    fn block(&self) -> String {
+        format!("{BEGIN_MARKER}\n{}\n{END_MARKER}\n", self.command)
+    }
+
+    /// Whether `script` runs gimoji: it has our markers, it's one of the
+    /// global hooks that does, or it's the whole script earlier versions
+    /// wrote without markers.
+    fn is_in(&self, script: &str) -> bool {
+        script.contains(BEGIN_MARKER)
+            || (script.starts_with(GLOBAL_HEADER) && script.contains(self.command))
+            || self.is_legacy(script)
+    }
+
+    fn is_legacy(&self, script: &str) -> bool {
+        script.trim_end() == format!("{HEADER}{}", self.command)
+    }
+
+    /// `script` with gimoji's part taken out.
+    fn without(&self, script: &str) -> String {
+        if self.is_legacy(script) {
+            return String::new();
+        }
+        let mut inside = false;
+
+        script
+            .split_inclusive('\n')
+            .filter(|line| {
+                let marker = match line.trim_end() {
+                    BEGIN_MARKER => Some(true),
+                    END_MARKER => Some(false),
+                    _ => None,
+                };
+                let keep = !inside && marker.is_none();
+                inside = marker.unwrap_or(inside);
+
+                keep
+            })
+            .collect()
+    }
+}
+
+/// Point the user's global `core.hooksPath` at a directory of gimoji's own,
+/// so every repository runs gimoji without `--init`. Its scripts run the
+/// hooks git would have run otherwise first: those of the global
+/// `core.hooksPath` it replaces, if there's one, or else each repository's
+/// own.
+pub fn install_global() -> Result<(), Box<dyn Error>> {
+    let dir = global_dir()?;
+    let previous = git::global_hooks_path();
+    if previous.as_deref() == dir.to_str() {
+        println!(
+            "gimoji is already in the global hooks, in `{}`",
+            dir.display()
+        );
+
+        return Ok(());
+    }
+
+    fs::create_dir_all(&dir)?;
+    let chained = match &previous {
+        Some(previous) => {
+            fs::write(dir.join(PREVIOUS_FILE), previous)?;
+            shell_path(previous)
+        }
+        None => {
+            remove_if_present(&dir.join(PREVIOUS_FILE))?;
+            REPOSITORY_HOOKS.to_string()
+        }
+    };
+    for name in HOOK_NAMES {
+        let script = if name == PREPARE_COMMIT_MSG.name {
+            format!("{GLOBAL_HEADER}hook={chained}/{name}\n{GLOBAL_GIMOJI}")
+        } else {
+            format!("{GLOBAL_HEADER}hook={chained}/{name}\n{GLOBAL_CHAIN}")
+        };
+        let path = dir.join(name);
+        fs::write(&path, script)?;
+        #[cfg(unix)]
+        fs::set_permissions(&path, Permissions::from_mode(0o755))?;
+    }
+    git::set_global_hooks_path(Some(dir.to_str().ok_or("non-UTF-8 config directory")?))?;
+
+    println!("Set the global `core.hooksPath` to `{}`", dir.display());
+    match previous {
+        Some(previous) => println!("Its hooks run those of `{previous}` first"),
+        None => println!("Its hooks run those of each repository first"),
+    }
+    println!("Repositories with a `core.hooksPath` of their own still need `gimoji --init`");
+
+    Ok(())
+}
+
+/// Undo [`install_global`], putting back the global `core.hooksPath` it
+/// replaced. Returns whether it was installed.
+pub fn uninstall_global() -> Result<bool, Box<dyn Error>> {
+    let (dir, installed) = global_status()?;
+    if installed {
+        let previous = read(&dir.join(PREVIOUS_FILE))?;
+        git::set_global_hooks_path(previous.as_deref())?;
+        match previous {
+            Some(previous) => println!("Set the global `core.hooksPath` back to `{previous}`"),
+            None => println!("Unset the global `core.hooksPath`"),
+        }
+    }
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+        println!("Removed `{}`", dir.display());
+    }
+
+    Ok(installed)
+}
+
+/// Where [`install_global`] puts the hooks, and whether git uses them.
+pub fn global_status() -> Result<(PathBuf, bool), Box<dyn Error>> {
+    let dir = global_dir()?;
+    let installed = git::global_hooks_path().as_deref() == dir.to_str();
+
+    Ok((dir, installed))
+}
+
+fn global_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let dir = config::user_dir().ok_or("no home directory for the global hooks")?;
+
+    Ok(dir.join("hooks"))
+}
+
+/// Whether the hook at `path` is one of the global hooks.
+fn is_global(path: &Path) -> bool {
+    global_dir().is_ok_and(|dir| path.parent() == Some(&dir))
+}
+
+/// `path`, as git reads it in `core.hooksPath`, for a shell script: `~` is
+/// the home directory, and relative paths are relative to the working tree.
+fn shell_path(path: &str) -> String {
+    let quoted = |s: &str| format!("'{}'", s.replace('\'', r"'\''"));
+    if let Some(rest) = path.strip_prefix("~/") {
+        format!("\"$HOME\"/{}", quoted(rest))
+    } else if Path::new(path).is_absolute() {
+        quoted(path)
+    } else {
+        format!("\"$(git rev-parse --show-toplevel)\"/{}", quoted(path))
+    }
+}
+
+/// Whether `script` runs in sh or bash, going by its shebang, so that
+/// gimoji's command can go at the end of it.
+fn is_shell(script: &str) -> bool {
+    let Some(shebang) = script
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("#!"))
+    else {
+        return false;
+    };
+    let mut words = shebang.split_whitespace();
+    let mut program = words.next().unwrap_or_default();
+    if program.ends_with("/env") {
+        program = words
+            .find(|word| !word.starts_with('-'))
+            .unwrap_or_default();
+    }
+
+    matches!(program.rsplit('/').next(), Some("sh" | "bash"))
+}
+
+fn remove_if_present(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// The contents of the file at `path`, if there's one.
+fn read(path: &Path) -> Result<Option<String>, Box<dyn Error>> {
+    match fs::read_to_string(path) {
+        Ok(script) => Ok(Some(script)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+const HEADER: &str = "#!/usr/bin/env bash\n# gimoji as a commit hook\n";
+/// Every hook githooks(5) lists, all of which the global hooks chain to, as
+/// git looks for them in the global `core.hooksPath` only.
+const HOOK_NAMES: [&str; 28] = [
+    "applypatch-msg",
+    "pre-applypatch",
+    "post-applypatch",
+    "pre-commit",
+    "pre-merge-commit",
+    "prepare-commit-msg",
+    "commit-msg",
+    "post-commit",
+    "pre-rebase",
+    "post-checkout",
+    "post-merge",
+    "pre-push",
+    "pre-receive",
+    "update",
+    "proc-receive",
+    "post-receive",
+    "post-update",
+    "reference-transaction",
+    "push-to-checkout",
+    "pre-auto-gc",
+    "post-rewrite",
+    "sendemail-validate",
+    "fsmonitor-watchman",
+    "p4-changelist",
+    "p4-prepare-changelist",
+    "p4-post-changelist",
+    "p4-pre-submit",
+    "post-index-change",
+];
+/// Where the global `core.hooksPath` that `install_global` replaced is kept.
+const PREVIOUS_FILE: &str = "previous-hooks-path";
+/// The hooks directory of the repository a hook runs in.
+const REPOSITORY_HOOKS: &str = "\"$(git rev-parse --git-common-dir)/hooks\"";
+const GLOBAL_HEADER: &str = "#!/bin/sh\n# Installed by `gimoji --init --global`\n";
+/// Runs the chained `$hook`, keeping its arguments, input and exit status.
+const GLOBAL_CHAIN: &str = r#"if [ -x "$hook" ]; then
+    exec "$hook" "$@"
+fi
+"#;
+/// Runs the chained `$hook` and then gimoji, unless the hook does already:
+/// it has [`BEGIN_MARKER`], or the command of scripts from earlier versions.
+const GLOBAL_GIMOJI: &str = r#"if [ -x "$hook" ]; then
+    "$hook" "$@" || exit $?
+    grep -qE '^# >>> gimoji >>>$|^gimoji --hook ' "$hook" && exit 0
+fi
+gimoji --hook "$1" "$2"
+"#;
+const BEGIN_MARKER: &str = "# >>> gimoji >>>";
+const END_MARKER: &str = "# <<< gimoji <<<";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gimoji_is_taken_out_of_scripts_it_was_added_to() {
+        let hook = PREPARE_COMMIT_MSG;
+        let other = "#!/bin/sh\nrun-checks \"$1\"\n";
+        let script = format!("{other}{}", hook.block());
+        assert!(hook.is_in(&script));
+        assert!(!hook.is_in(other));
+        assert_eq!(hook.without(&script), other);
+
+        let around = format!("{other}{}echo done\n", hook.block());
+        assert_eq!(hook.without(&around), format!("{other}echo done\n"));
+    }
+
+    #[test]
+    fn scripts_from_earlier_versions_are_recognized() {
+        let legacy = format!("{HEADER}{}", COMMIT_MSG.command);
+        assert!(COMMIT_MSG.is_in(&legacy));
+        assert!(!PREPARE_COMMIT_MSG.is_in(&legacy));
+        assert_eq!(COMMIT_MSG.without(&legacy), "");
+    }
+
+    #[test]
+    fn only_sh_and_bash_scripts_are_added_to() {
+        assert!(is_shell("#!/bin/sh\nset -e\n"));
+        assert!(is_shell("#! /usr/bin/bash -eu\n"));
+        assert!(is_shell("#!/usr/bin/env bash\n"));
+        assert!(is_shell("#!/usr/bin/env -S sh -e\n"));
+        assert!(!is_shell("#!/usr/bin/env python3\n"));
+        assert!(!is_shell("#!/bin/zsh\n"));
+        assert!(!is_shell("run-checks\n"));
+    }
+
+    #[test]
+    fn global_hooks_look_for_gimoji_by_its_marker_or_command() {
+        let pattern = format!("'^{BEGIN_MARKER}$|^gimoji --hook '");
+        assert!(GLOBAL_GIMOJI.contains(&pattern));
+        assert!(PREPARE_COMMIT_MSG.command.starts_with("gimoji --hook "));
+    }
+
+    #[test]
+    fn previous_hooks_paths_resolve_like_git_does() {
+        assert_eq!(shell_path("/etc/hooks"), "'/etc/hooks'");
+        assert_eq!(shell_path("~/my hooks"), "\"$HOME\"/'my hooks'");
+        assert_eq!(
+            shell_path(".githooks"),
+            "\"$(git rev-parse --show-toplevel)\"/'.githooks'"
+        );
+        assert_eq!(shell_path("/it's"), r"'/it'\''s'");
+    }
+}
@@ -0,0 +1,312 @@
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt, fs,
+    io::{self, Read},
+    path::PathBuf,
+    process::exit,
+};
+
+use gimoji_core::{detect_prefix_with, lookup, ConventionalHeader, Emoji};
+
+use crate::{config::Config, git, hook, message::CommitMessage};
+
+/// Check that commit messages start with an emoji, without the network access the commitlint
+/// plugin needs.
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The commit message file to check, e.g. the one git passes to a `commit-msg` hook. Reads
+    /// standard input if missing or `-`.
+    #[arg(conflicts_with = "range")]
+    file: Option<PathBuf>,
+
+    /// Check the subjects of the commits in this range instead, e.g. `origin/main..HEAD`.
+    #[arg(long, value_name = "RANGE")]
+    range: Option<String>,
+
+    /// Also require a Conventional Commits header (`type(scope): `) in front of the emoji, with
+    /// the type that goes with it.
+    #[arg(long)]
+    conventional: bool,
+
+    /// Install `gimoji lint` as a `commit-msg` hook.
+    #[arg(long, conflicts_with_all = ["file", "range"])]
+    init: bool,
+}
+
+pub fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    if args.init {
+        return hook::COMMIT_MSG.install();
+    }
+
+    let config = Config::load()?;
+    let rules = Rules::new(&config, args.conventional);
+    let subjects = match (&args.range, &args.file) {
+        (Some(range), _) => git::commits(range)?,
+        (None, file) => {
+            let (name, text) = match file.as_deref().filter(|path| path.as_os_str() != "-") {
+                Some(path) => (path.display().to_string(), fs::read_to_string(path)?),
+                None => {
+                    let mut text = String::new();
+                    io::stdin().read_to_string(&mut text)?;
+                    ("stdin".to_string(), text)
+                }
+            };
+            let message = CommitMessage::parse(text, &git::comment_string(), None);
+            let subject = message.subject().lines().next().unwrap_or_default();
+
+            vec![(name, subject.to_string())]
+        }
+    };
+
+    let mut failed = 0;
+    for (name, subject) in &subjects {
+        if let Err(problem) = rules.check(subject) {
+            eprintln!("{name}: {subject}\n  {problem}");
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        eprintln!(
+            "\n{failed} of {} commit message(s) failed the check; run `gimoji` to pick an emoji.",
+            subjects.len()
+        );
+        exit(1);
+    }
+
+    Ok(())
+}
+
+/// What a commit subject has to start with.
+pub struct Rules {
+    /// The emojis allowed: the picker's.
+    emojis: Cow<'static, [Emoji]>,
+    /// The config's own emojis, for recognizing them.
+    extra: Vec<Emoji>,
+    conventional: bool,
+}
+
+impl Rules {
+    /// The rules `config` sets, requiring a Conventional Commits header too
+    /// if `conventional`.
+    pub fn new(config: &Config, conventional: bool) -> Self {
+        Self {
+            emojis: config.emojis.picker_emojis(),
+            extra: config.emojis.extra_emojis(),
+            conventional,
+        }
+    }
+
+    /// What's wrong with `subject`, if anything.
+    ///
+    /// Empty subjects pass, leaving git to reject them, as do those git
+    /// writes itself for merges, reverts and autosquash.
+    pub fn check(&self, subject: &str) -> Result<(), Problem<'_>> {
+        let subject = subject.trim_start();
+        if subject.is_empty() || IGNORED_PREFIXES.iter().any(|p| subject.starts_with(p)) {
+            return Ok(());
+        }
+
+        let header = ConventionalHeader::parse(subject);
+        let start = header.map_or(0, |header| header.end());
+        let Some(emoji) = self.emoji_at(&subject[start..]) else {
+            return Err(Problem::NoEmoji {
+                suggestions: self.suggestions(subject, header),
+            });
+        };
+        if !self.conventional {
+            return Ok(());
+        }
+
+        match (header, emoji.commit_type()) {
+            (None, _) => Err(Problem::NoHeader { emoji }),
+            (Some(header), Some(commit_type)) if header.commit_type() != commit_type => {
+                Err(Problem::TypeMismatch {
+                    found: header.commit_type().to_string(),
+                    emoji,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The allowed emoji `text` starts with.
+    fn emoji_at(&self, text: &str) -> Option<&Emoji> {
+        let (emoji, _) = detect_prefix_with(text, &self.extra)?;
+
+        self.allowed(emoji)
+    }
+
+    /// The picker's own `emoji`, if it offers one with its code.
+    fn allowed(&self, emoji: &Emoji) -> Option<&Emoji> {
+        self.emojis
+            .iter()
+            .find(|allowed| allowed.code() == emoji.code())
+    }
+
+    /// Emojis `subject` could start with: those of its Conventional Commits
+    /// type if it has a header, or else those named like its first word.
+    fn suggestions(&self, subject: &str, header: Option<ConventionalHeader<'_>>) -> Vec<&Emoji> {
+        let mut suggestions: Vec<&Emoji> = header
+            .map(|header| {
+                self.emojis
+                    .iter()
+                    .filter(|emoji| emoji.commit_type() == Some(header.commit_type()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if suggestions.is_empty() {
+            let word = match header {
+                Some(header) => header.commit_type(),
+                None => subject.split_whitespace().next().unwrap_or_default(),
+            };
+            suggestions = match lookup(word) {
+                Ok(emoji) => vec![emoji],
+                Err(e) => e.suggestions().to_vec(),
+            }
+            .into_iter()
+            .filter_map(|emoji| self.allowed(emoji))
+            .collect();
+        }
+        suggestions.truncate(MAX_SUGGESTIONS);
+
+        suggestions
+    }
+}
+
+/// Why a commit subject fails [`Rules::check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem<'r> {
+    /// It doesn't start with an allowed emoji.
+    NoEmoji { suggestions: Vec<&'r Emoji> },
+    /// It lacks the Conventional Commits header `--conventional` requires.
+    NoHeader { emoji: &'r Emoji },
+    /// Its Conventional Commits type isn't the one of its emoji.
+    TypeMismatch { found: String, emoji: &'r Emoji },
+}
+
+impl fmt::Display for Problem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::NoEmoji { suggestions } => {
+                f.write_str("doesn't start with a gimoji emoji")?;
+                for (i, emoji) in suggestions.iter().enumerate() {
+                    let sep = if i == 0 { "; did you mean" } else { "," };
+                    write!(f, "{sep} {} {}", emoji.emoji(), emoji.code())?;
+                }
+                if !suggestions.is_empty() {
+                    f.write_str("?")?;
+                }
+
+                Ok(())
+            }
+            Problem::NoHeader { emoji } => {
+                f.write_str("doesn't start with a Conventional Commits `type: ` header")?;
+                match emoji.commit_type() {
+                    Some(commit_type) => {
+                        write!(f, "; for {} that's `{commit_type}: `", emoji.emoji())
+                    }
+                    None => Ok(()),
+                }
+            }
+            Problem::TypeMismatch { found, emoji } => write!(
+                f,
+                "the `{found}` type doesn't go with {} {}, whose type is `{}`",
+                emoji.emoji(),
+                emoji.code(),
+                emoji.commit_type().unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// How subjects git writes itself start.
+const IGNORED_PREFIXES: [&str; 5] = ["Merge ", "Revert ", "fixup! ", "squash! ", "amend! "];
+const MAX_SUGGESTIONS: usize = 3;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(config: &str, conventional: bool) -> Rules {
+        Rules::new(&toml::from_str(config).unwrap(), conventional)
+    }
+
+    fn codes(problem: Problem<'_>) -> Vec<String> {
+        let Problem::NoEmoji { suggestions } = problem else {
+            panic!("expected NoEmoji, got {problem:?}");
+        };
+
+        suggestions.iter().map(|e| e.code().to_string()).collect()
+    }
+
+    #[test]
+    fn subjects_must_start_with_an_emoji() {
+        let rules = rules("", false);
+        assert_eq!(rules.check("🐛 Fix it"), Ok(()));
+        assert_eq!(rules.check(":bug: Fix it"), Ok(()));
+        assert_eq!(rules.check("⚡ Speed it up"), Ok(()));
+        assert_eq!(rules.check("fix: 🐛 Fix it"), Ok(()));
+        assert!(rules.check("Fix it").is_err());
+    }
+
+    #[test]
+    fn what_git_writes_itself_passes() {
+        let rules = rules("", false);
+        for subject in [
+            "",
+            "Merge branch 'main'",
+            "Revert \"🐛 Fix it\"",
+            "fixup! Fix it",
+        ] {
+            assert_eq!(rules.check(subject), Ok(()), "{subject:?}");
+        }
+    }
+
+    #[test]
+    fn failures_suggest_emojis() {
+        let rules = rules("", false);
+        assert_eq!(codes(rules.check("bug: Fix it").unwrap_err()), [":bug:"]);
+        assert!(codes(rules.check("bgu Fix it").unwrap_err()).contains(&":bug:".to_string()));
+        let perf = codes(rules.check("perf: Speed it up").unwrap_err());
+        assert_eq!(perf, [":zap:"]);
+        assert!(rules
+            .check("Fix it")
+            .unwrap_err()
+            .to_string()
+            .contains("gimoji emoji"));
+    }
+
+    #[test]
+    fn config_decides_the_allowed_emojis() {
+        let rules = rules(
+            r#"
+            [emojis]
+            disabled = ["beers"]
+            [[emojis.extra]]
+            emoji = "🦀"
+            code = "crab"
+            description = "Rust-specific change."
+            "#,
+            false,
+        );
+        assert_eq!(rules.check("🦀 Use more Rust"), Ok(()));
+        assert!(rules.check("🍻 Cheers").is_err());
+    }
+
+    #[test]
+    fn conventional_headers_must_match_the_emoji() {
+        let rules = rules("", true);
+        assert_eq!(rules.check("fix(core): 🐛 Fix it"), Ok(()));
+        assert_eq!(rules.check("fix!: 🚑️ Fix it now"), Ok(()));
+
+        let problem = rules.check("🐛 Fix it").unwrap_err();
+        assert!(matches!(problem, Problem::NoHeader { .. }));
+        assert!(problem.to_string().contains("`fix: `"));
+        let problem = rules.check("feat: 🐛 Fix it").unwrap_err();
+        assert_eq!(
+            problem.to_string(),
+            "the `feat` type doesn't go with 🐛 :bug:, whose type is `fix`"
+        );
+    }
+}
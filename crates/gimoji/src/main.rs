@@ -1,29 +1,56 @@
+mod bump;
+mod changelog;
 mod clipboard;
+mod config;
 mod event_to_action;
+mod git;
+mod history;
+mod hook;
+mod lint;
+mod message;
+mod suggest;
 mod terminal;
 
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
+use config::{Behaviour, Config};
 use crossterm::event::{read, Event};
-use gimoji_core::{App, Colors, Outcome, EMOJIS};
-use std::{
-    error::Error,
-    fs::{File, OpenOptions},
-    io::{BufRead, BufReader, Read, Write},
-    process::exit,
+use event_to_action::Keymap;
+use gimoji_core::{
+    detect_prefix_with, keywords, lookup, App, Colors, ConventionalHeader, Details, Emoji, History,
+    HistoryEntry, Outcome, Preview, Template,
 };
-#[cfg(unix)]
-use std::{fs::Permissions, os::unix::prelude::PermissionsExt};
+use message::CommitMessage;
+use serde::Deserialize;
+use std::{error::Error, fs, path::Path, process::exit};
 
 use terminal::Terminal;
 
 /// Select emoji for git commit message.
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
-    /// Initialize gimoji as a commit message (`prepare-commit-msg`) hook.
-    #[arg(short, long)]
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Initialize gimoji as a commit message (`prepare-commit-msg`) hook, adding it to the end of
+    /// the hook script if there's a sh or bash one already.
+    #[arg(short, long, group = "manage")]
     init: bool,
 
+    /// Take gimoji back out of the repository's hooks, including the `gimoji lint` one.
+    #[arg(long, group = "manage")]
+    uninstall: bool,
+
+    /// Report which of the repository's hooks run gimoji, failing if none do.
+    #[arg(long, group = "manage")]
+    check: bool,
+
+    /// With `--init`, `--uninstall` or `--check`: manage gimoji's global hooks instead, which
+    /// every repository on the machine runs. They're set as the global `core.hooksPath`, and run
+    /// the hooks each repository (or the previous global `core.hooksPath`) has first.
+    #[arg(long, requires = "manage")]
+    global: bool,
+
     /// Run as git commit hook.
     #[arg(long, value_delimiter = ' ', num_args = 1..3)]
     hook: Vec<String>,
@@ -37,9 +64,42 @@ struct Args {
     /// Output the selected emoji to standard out. Note that this switches the UI to render via stderr.
     #[arg(short, long)]
     stdout: bool,
+
+    /// Use this emoji (glyph, `:code:` or name) instead of prompting for one. Separate several
+    /// with commas, e.g. `memo,bug`.
+    ///
+    /// Works without a terminal, e.g. from scripts and CI bots.
+    #[arg(short, long, value_name = "EMOJI", value_delimiter = ',')]
+    pick: Vec<String>,
+
+    /// Show how each emoji bumps the version (major, minor or patch) in the list.
+    #[arg(long)]
+    show_semver: bool,
+
+    /// How to write out the emoji, e.g. `{code} ` for `:bug: ` or `{type}: {emoji} ` for
+    /// `fix: 🐛 `. Placeholders are `{emoji}`, `{code}`, `{name}`, `{description}`, `{type}`, the
+    /// emoji's Conventional Commits type, and `{scope}`.
+    ///
+    /// Applies to the commit message in hook mode, where it defaults to `{emoji} `, and to
+    /// `--stdout`, where it defaults to the bare emoji.
+    #[arg(long, value_name = "TEMPLATE")]
+    prefix: Option<Template>,
+
+    /// In hook mode, offer to replace the emoji the message already starts with (e.g. when
+    /// amending) instead of leaving the message alone.
+    #[arg(long)]
+    replace: bool,
 }
 
-#[derive(ValueEnum, Debug, Clone, Copy)]
+#[derive(Subcommand, Debug)]
+enum Command {
+    Bump(bump::Args),
+    Changelog(changelog::Args),
+    Lint(lint::Args),
+}
+
+#[derive(ValueEnum, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
 enum ColorScheme {
     Light,
     Dark,
@@ -57,66 +117,307 @@ impl From<ColorScheme> for Colors {
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
 
+    if let Some(command) = &args.command {
+        return match command {
+            Command::Bump(args) => bump::run(args),
+            Command::Changelog(args) => changelog::run(args),
+            Command::Lint(args) => lint::run(args),
+        };
+    }
+
+    if args.global {
+        if args.init {
+            hook::install_global()?;
+        } else if args.uninstall {
+            if !hook::uninstall_global()? {
+                println!("gimoji's global hooks aren't installed");
+            }
+        } else {
+            let (dir, installed) = hook::global_status()?;
+            let status = if installed {
+                "installed"
+            } else {
+                "not installed"
+            };
+            println!("global hooks: {status} ({})", dir.display());
+            if !installed {
+                exit(1);
+            }
+        }
+
+        return Ok(());
+    }
     if args.init {
-        install_hook()?;
+        hook::PREPARE_COMMIT_MSG.install()?;
 
         return Ok(());
     }
+    if args.uninstall {
+        let mut removed = false;
+        for hook in hook::ALL {
+            removed |= hook.uninstall()?;
+        }
+        if !removed {
+            println!("None of the repository's hooks run gimoji");
+        }
 
-    let (commit_file_path, commit_file_content) = if !args.hook.is_empty() {
-        let path = &args.hook[0];
-        let file = File::open(path)?;
-        let mut reader = BufReader::new(file);
-        let mut content = String::new();
-        reader.read_line(&mut content)?;
-        let content = if !content.is_empty() {
-            // FIXME: There has to be a faster way to detect an emoji.
-            for emoji in EMOJIS {
-                if content.contains(emoji.emoji()) || content.contains(emoji.code()) {
-                    // The commit shortlog already contains an emoji.
-                    return Ok(());
-                }
-            }
+        return Ok(());
+    }
+    if args.check {
+        let mut installed = false;
+        for hook in hook::ALL {
+            let (path, is_in) = hook.status()?;
+            let status = if is_in { "installed" } else { "not installed" };
+            println!("{}: {status} ({})", hook.name(), path.display());
+            installed |= is_in;
+        }
+        if !installed {
+            exit(1);
+        }
 
-            // Load the rest of the file.
-            reader.read_to_string(&mut content)?;
-            Some(content)
-        } else {
-            None
-        };
+        return Ok(());
+    }
 
-        (Some(path), content)
+    let config = Config::load()?;
+    let extra = config.emojis.extra_emojis();
+    let replace = args.replace || config.hook.replace == Some(true);
+    // The emojis the message already starts with, where they start past any
+    // Conventional Commits header, and how many bytes they take up.
+    let mut existing = None;
+    // Whether to pick without prompting, as the commit's source calls for.
+    let mut auto = false;
+    let commit_message = if !args.hook.is_empty() {
+        // Commits being replayed keep the messages they have.
+        if config.hook.enabled == Some(false) || git::sequencer_in_progress()? {
+            return Ok(());
+        }
+        let source = args.hook.get(1).map(String::as_str);
+        match config.hook.behaviour(source) {
+            Behaviour::Skip => return Ok(()),
+            Behaviour::Prompt => {}
+            Behaviour::Auto => auto = true,
+        }
+        let path = Path::new(&args.hook[0]);
+        let text = fs::read_to_string(path)?;
+        let message = CommitMessage::parse(text, &git::comment_string(), source);
+        let subject = message.subject();
+        let start = ConventionalHeader::parse(subject).map_or(0, |header| header.end());
+        let mut current = Vec::new();
+        let mut len = 0;
+        while let Some((emoji, emoji_len)) = detect_prefix_with(&subject[start + len..], &extra) {
+            current.push(config.emojis.typed(emoji));
+            len += emoji_len;
+        }
+        if !current.is_empty() {
+            if !replace {
+                // The commit shortlog already starts with an emoji.
+                return Ok(());
+            }
+            existing = Some((current, start, len));
+        }
+
+        Some((path, message))
     } else {
-        (None, None)
+        None
     };
 
-    let color_scheme = get_color_scheme(&args);
-    let selected = match select_emoji(color_scheme.into(), args.stdout)? {
-        Some(s) => s,
-        None => return Ok(()),
+    let mut history = history::FileHistory::new();
+    let mut entries = match history.as_mut().map(History::load).transpose() {
+        Ok(entries) => entries.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("WARNING: Failed to load the history of picked emojis: {e}");
+
+            Vec::new()
+        }
+    };
+    let first_line = match &commit_message {
+        Some((_, message)) => message.subject().lines().next().unwrap_or_default(),
+        None => "",
     };
+    // Never replace an emoji the user already chose without asking them.
+    let auto_pick = if auto && existing.is_none() {
+        match auto_emoji(&config, first_line) {
+            Some(query) => Some(query),
+            None => return Ok(()),
+        }
+    } else {
+        None
+    };
+    // Only ask for the rest of the subject if there's none yet.
+    let prompts = commit_message.is_none() || (existing.is_none() && first_line.trim().is_empty());
+    let queries: Vec<&str> = match &auto_pick {
+        Some(query) if args.pick.is_empty() => vec![query],
+        _ => args.pick.iter().map(String::as_str).collect(),
+    };
+    let (selected, details) = if !queries.is_empty() {
+        let mut selected = Vec::new();
+        for query in queries {
+            let picked = match extra.iter().find(|emoji| config::names(query, emoji)) {
+                Some(emoji) => emoji,
+                None => lookup(query).unwrap_or_else(|e| {
+                    eprintln!("{e}");
+                    exit(1);
+                }),
+            };
+            gimoji_core::history::record(&mut entries, picked.emoji());
+            selected.push(config.emojis.typed(picked));
+        }
 
-    if let Some(path) = commit_file_path {
-        // Just prepend the emoji to the file.
-        let mut file = File::create(path)?;
-        let prefix = format!("{selected} ");
-        file.write_all(prefix.as_bytes())?;
-        if let Some(content) = commit_file_content {
-            file.write_all(content.as_bytes())?;
+        (selected, Details::default())
+    } else {
+        let color_scheme = get_color_scheme(&args, &config);
+        let colors = color_scheme.into();
+        let commit = match &commit_message {
+            Some(_) => {
+                let changes = staged_changes();
+                let preview = (config.hook.preview != Some(false)).then(|| {
+                    // The subject past the emojis it might start with already.
+                    let rest = match &existing {
+                        Some((_, start, len)) => &first_line[start + len..],
+                        None => first_line,
+                    };
+                    let files = changes.iter().map(ToString::to_string).collect();
+                    let template = args.prefix.as_ref().or(config.prefix.as_ref());
+                    Preview::new(rest.trim_start(), files).with_template(
+                        template
+                            .cloned()
+                            .unwrap_or_else(|| config.prompts.default_prefix()),
+                    )
+                });
+
+                Commit {
+                    current: existing.as_ref().map(|(emojis, _, _)| emojis[0].code()),
+                    subject: first_line,
+                    suggested: suggest::suggestions(&config.suggestions, &changes),
+                    preview,
+                }
+            }
+            None => Commit::default(),
+        };
+        match select_emoji(colors, &args, &config, commit, prompts, &mut entries)? {
+            Some(picked) => picked,
+            None => return Ok(()),
         }
+    };
+    // Before the output: copying to the clipboard doesn't return.
+    if let Some(Err(e)) = history.as_mut().map(|h| h.save(&entries)) {
+        eprintln!("WARNING: Failed to save the history of picked emojis: {e}");
+    }
+
+    let default_prefix = config.prompts.default_prefix();
+    let prefix = args.prefix.as_ref().or(config.prefix.as_ref());
+    if let Some((path, message)) = commit_message {
+        let message = match existing {
+            Some((current, start, len)) => message.with_replaced(&current, start, len, &selected),
+            None => message.with_prefix(
+                &subject(prefix.unwrap_or(&default_prefix), &selected, &details),
+                0,
+            ),
+        };
+        message::write_atomically(path, &message)?;
     } else if args.stdout {
-        println!("{selected}");
+        match prefix {
+            None if details == Details::default() => println!("{}", glyphs(&selected)),
+            _ => println!(
+                "{}",
+                subject(prefix.unwrap_or(&default_prefix), &selected, &details)
+            ),
+        }
     } else {
-        println!("Copied {selected} to the clipboard");
-        gimoji_core::Clipboard::copy(&mut clipboard::ArboardImpl, &selected)?;
+        let text = if details == Details::default() {
+            glyphs(&selected)
+        } else {
+            subject(prefix.unwrap_or(&default_prefix), &selected, &details)
+        };
+        println!("Copied {text} to the clipboard");
+        gimoji_core::Clipboard::copy(&mut clipboard::ArboardImpl, &text)?;
     }
 
     Ok(())
 }
 
-fn select_emoji(colors: Colors, use_stderr: bool) -> Result<Option<String>, Box<dyn Error>> {
-    let mut terminal = Terminal::setup(use_stderr)?;
-    let mut app = App::new(EMOJIS, &colors);
+/// The subject line, or its start, for `selected` with the `details` typed
+/// in: `prefix` and the summary.
+fn subject(prefix: &Template, selected: &[Emoji], details: &Details) -> String {
+    prefix.render_many(selected, details.scope.as_deref()) + &details.summary
+}
+
+/// The emoji `auto` applies to a commit with `subject`, by glyph, `:code:`
+/// or name: the first one the subject's words hint at that the picker
+/// offers, or else the `default-emoji`, if any.
+fn auto_emoji(config: &Config, subject: &str) -> Option<String> {
+    let offered = config.emojis.picker_emojis();
+    keywords::hinted(subject)
+        .into_iter()
+        .find(|hinted| offered.iter().any(|emoji| emoji.code() == hinted.code()))
+        .map(|hinted| hinted.code().to_string())
+        .or_else(|| config.hook.default_emoji.clone())
+}
+
+/// The glyphs of `emojis`, one after the other.
+fn glyphs(emojis: &[Emoji]) -> String {
+    emojis.iter().map(Emoji::emoji).collect()
+}
+
+/// The changes staged for the commit, if git can tell.
+fn staged_changes() -> Vec<suggest::Change> {
+    git::staged_changes().unwrap_or_else(|e| {
+        eprintln!("WARNING: Failed to read the staged changes: {e}");
+
+        Vec::new()
+    })
+}
+
+/// The emojis picked, in order, and the details typed in after them.
+type Selection = (Vec<Emoji>, Details);
+
+/// What the hook knows about the commit, for the picker to start from.
+#[derive(Default)]
+struct Commit<'m> {
+    /// Code of the emoji the message already starts with.
+    current: Option<&'m str>,
+    /// The subject line written so far.
+    subject: &'m str,
+    /// Emojis suggested for the staged changes, by glyph, `:code:` or name.
+    suggested: Vec<String>,
+    preview: Option<Preview>,
+}
+
+/// Let the user pick emojis for the `commit`, listing those it suggests
+/// and then the most used ones in `history` first, and starting from its
+/// current one, if any; then ask for the details the config wants if
+/// `prompts`. The pick is added to `history`.
+fn select_emoji(
+    colors: Colors,
+    args: &Args,
+    config: &Config,
+    commit: Commit<'_>,
+    prompts: bool,
+    history: &mut Vec<HistoryEntry>,
+) -> Result<Option<Selection>, Box<dyn Error>> {
+    let keymap = Keymap::new(&config.keybindings);
+    let emojis = config.emojis.picker_emojis();
+    let mut terminal = Terminal::setup(args.stdout)?;
+    let suggested = commit
+        .suggested
+        .iter()
+        .filter_map(|query| emojis.iter().find(|emoji| config::names(query, emoji)))
+        .map(|emoji| emoji.emoji().to_string())
+        .collect();
+    let mut app = App::new(emojis, &colors);
+    app.set_show_semver(args.show_semver);
+    app.set_grouped(config.group_by_category == Some(true));
+    app.set_history(std::mem::take(history));
+    app.set_suggested(suggested);
+    app.set_subject(commit.subject);
+    app.set_preview(commit.preview);
+    if let Some(code) = commit.current {
+        app.select(code);
+    }
+    if prompts {
+        let ask = |prompt: Option<bool>| prompt == Some(true);
+        app.set_prompts(ask(config.prompts.scope), ask(config.prompts.summary));
+    }
 
     loop {
         terminal.draw(|f| app.render(f))?;
@@ -124,50 +425,39 @@ fn select_emoji(colors: Colors, use_stderr: bool) -> Result<Option<String>, Box<
         let Event::Key(event) = read()? else {
             continue;
         };
-        let action = match event_to_action::from_key_event(event, app.search_text().is_empty()) {
-            Ok(Some(a)) => a,
-            Ok(None) => continue,
-            Err(_) => {
-                let _ = terminal.cleanup();
-                exit(130);
-            }
-        };
+        let search_is_empty = app.input_text().is_empty();
+        let prompting = app.is_prompting();
+        let action =
+            match event_to_action::from_key_event(event, search_is_empty, prompting, &keymap) {
+                Ok(Some(a)) => a,
+                Ok(None) => continue,
+                Err(_) => {
+                    let _ = terminal.cleanup();
+                    exit(130);
+                }
+            };
 
         match app.handle(action) {
             Outcome::Continue => {}
-            Outcome::Picked(s) => return Ok(Some(s)),
+            Outcome::Picked(emoji) => {
+                *history = app.history().to_vec();
+                return Ok(Some((vec![emoji], Details::default())));
+            }
+            Outcome::PickedMany(emojis) => {
+                *history = app.history().to_vec();
+                return Ok(Some((emojis, Details::default())));
+            }
+            Outcome::Described(emojis, details) => {
+                *history = app.history().to_vec();
+                return Ok(Some((emojis, details)));
+            }
             Outcome::Cancelled => return Ok(None),
         }
     }
 }
 
-fn install_hook() -> Result<(), Box<dyn Error>> {
-    let mut file = match OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open(HOOK_PATH)
-    {
-        Ok(f) => f,
-        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
-            eprintln!(
-                "Failed to create `{HOOK_PATH}` as it already exists. \
-                Please either remove it and re-run `gimoji -i`, or \
-                add the following command line to it:\n{HOOK_CMD}",
-            );
-            exit(-1);
-        }
-        Err(e) => return Err(e.into()),
-    };
-    file.write_all(HOOK_HEADER.as_bytes())?;
-    file.write_all(HOOK_CMD.as_bytes())?;
-    #[cfg(unix)]
-    file.set_permissions(Permissions::from_mode(0o744))?;
-
-    Ok(())
-}
-
-// Color scheme selection. Precedence: env, arg, detection, default.
-fn get_color_scheme(args: &Args) -> ColorScheme {
+// Color scheme selection. Precedence: env, arg, config, detection, default.
+fn get_color_scheme(args: &Args, config: &Config) -> ColorScheme {
     std::env::var("GIMOJI_COLOR_SCHEME")
         .ok()
         .and_then(|s| match s.as_str() {
@@ -176,6 +466,7 @@ fn get_color_scheme(args: &Args) -> ColorScheme {
             _ => None,
         })
         .or(args.color_scheme)
+        .or(config.color_scheme)
         .unwrap_or_else(|| {
             terminal_light::luma()
                 .map(|l| {
@@ -192,7 +483,3 @@ fn get_color_scheme(args: &Args) -> ColorScheme {
                 })
         })
 }
-
-const HOOK_PATH: &str = ".git/hooks/prepare-commit-msg";
-const HOOK_HEADER: &str = "#!/usr/bin/env bash\n# gimoji as a commit hook\n";
-const HOOK_CMD: &str = "gimoji --hook \"$1\" \"$2\"";
@@ -0,0 +1,281 @@
+use std::{fs, io, path::Path};
+
+use gimoji_core::{ConventionalHeader, Emoji};
+
+/// A commit message as git hands it to `prepare-commit-msg`: whatever the
+/// user is to edit, with comment lines git strips afterwards mixed in, and
+/// with `--verbose`, a scissors line and the diff below it.
+pub struct CommitMessage {
+    text: String,
+    /// Byte offset of the line the emoji goes in front of, if there's one.
+    subject: Option<usize>,
+}
+
+impl CommitMessage {
+    /// Parse `text`, where lines starting with `comment` are comments,
+    /// except in a message from `source` `message`: git puts what was given
+    /// to `git commit -m` or `-F` on top as it is, and only strips comments
+    /// from it when an editor is opened after, so that a subject such as
+    /// "#123 Fix login" stays the subject.
+    pub fn parse(text: String, comment: &str, source: Option<&str>) -> Self {
+        let comment = (source != Some(MESSAGE_SOURCE)).then_some(comment);
+        let subject = find_subject(&text, comment);
+
+        Self { text, subject }
+    }
+
+    /// The message from its subject line on, so callers can check what it
+    /// starts with. Empty when there's no subject line.
+    pub fn subject(&self) -> &str {
+        self.subject.map_or("", |start| &self.text[start..])
+    }
+
+    /// The message with `prefix` put in front of the subject line, in place
+    /// of the first `replaced` bytes of it.
+    ///
+    /// A message without a line for the subject, which is one that is all
+    /// comments, gets a new line for the prefix on top; the prefix never ends
+    /// up on a line of its own above anything that is kept.
+    pub fn with_prefix(&self, prefix: &str, replaced: usize) -> String {
+        match self.subject {
+            Some(start) => [&self.text[..start], prefix, &self.text[start + replaced..]].concat(),
+            None if self.text.is_empty() => prefix.to_string(),
+            None => format!("{prefix}\n{}", self.text),
+        }
+    }
+
+    /// The message with `selected` in place of the `current` emojis, which
+    /// take up the `len` bytes of the subject line from `start`.
+    ///
+    /// Only the emojis are swapped, in the form they were written in, keeping
+    /// whatever the prefix put around them; except that a Conventional Commits
+    /// type that came with the current emojis goes with them too.
+    pub fn with_replaced(
+        &self,
+        current: &[Emoji],
+        start: usize,
+        len: usize,
+        selected: &[Emoji],
+    ) -> String {
+        let subject = self.subject();
+        let current_type = current.iter().find_map(Emoji::commit_type);
+        let mut replacement = String::new();
+        if let Some(header) = ConventionalHeader::parse(subject) {
+            let rest = &subject[header.commit_type().len()..start];
+            match selected.iter().find_map(Emoji::commit_type) {
+                Some(new) if current_type == Some(header.commit_type()) => {
+                    replacement.push_str(new);
+                    replacement.push_str(rest);
+                }
+                _ => replacement.push_str(&subject[..start]),
+            }
+        }
+        let codes = subject[start..].starts_with(':');
+        for emoji in selected {
+            replacement.push_str(if codes { emoji.code() } else { emoji.emoji() });
+        }
+
+        self.with_prefix(&replacement, start + len)
+    }
+}
+
+/// Write `contents` to `path` through a temporary file renamed over it, so
+/// an interruption, or another process writing it too, leaves either the
+/// old contents or the new, never a truncated mix.
+pub fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".gimoji-tmp-{}", std::process::id()));
+    let tmp = path.with_file_name(name);
+    fs::write(&tmp, contents)?;
+
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// Where the subject goes: the first line with text on it, or failing that
+/// the first blank one, that is neither a comment nor below the scissors;
+/// without a `comment` string, no line is either.
+fn find_subject(text: &str, comment: Option<&str>) -> Option<usize> {
+    let mut first_blank = None;
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = start;
+        start += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+        if let Some(comment) = comment.and_then(|comment| line.strip_prefix(comment)) {
+            if comment.trim() == SCISSORS {
+                break;
+            }
+            continue;
+        }
+        if line.trim().is_empty() {
+            first_blank.get_or_insert(line_start);
+            continue;
+        }
+
+        return Some(line_start);
+    }
+
+    first_blank
+}
+
+/// The hook source of messages given with `git commit -m` or `-F`.
+const MESSAGE_SOURCE: &str = "message";
+
+/// What follows the comment string on the line above which `git commit
+/// --verbose` puts the message, and below which everything is ignored.
+const SCISSORS: &str = "------------------------ >8 ------------------------";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefixed(text: &str) -> String {
+        CommitMessage::parse(text.to_string(), "#", None).with_prefix("🐛 ", 0)
+    }
+
+    #[test]
+    fn prefix_goes_in_front_of_the_subject() {
+        assert_eq!(prefixed("Fix it\n\nBody\n"), "🐛 Fix it\n\nBody\n");
+        assert_eq!(prefixed(""), "🐛 ");
+    }
+
+    #[test]
+    fn comments_above_the_subject_are_skipped() {
+        let template = "# Summarize the change:\n\n# Why:\nFix it\n";
+        assert_eq!(
+            prefixed(template),
+            "# Summarize the change:\n\n# Why:\n🐛 Fix it\n"
+        );
+    }
+
+    #[test]
+    fn an_empty_message_gets_the_prefix_on_its_blank_line() {
+        let fresh = "\n# Please enter the commit message for your changes.\n";
+        assert_eq!(
+            prefixed(fresh),
+            "🐛 \n# Please enter the commit message for your changes.\n"
+        );
+        let template = "# Summarize the change:\n\n# Why:\n";
+        assert_eq!(prefixed(template), "# Summarize the change:\n🐛 \n# Why:\n");
+    }
+
+    #[test]
+    fn all_comments_get_a_line_of_their_own() {
+        assert_eq!(prefixed("# Nothing here\n"), "🐛 \n# Nothing here\n");
+    }
+
+    #[test]
+    fn the_diff_below_the_scissors_is_not_the_subject() {
+        let verbose = format!("# Comments\n# {SCISSORS}\ndiff --git a/x b/x\n");
+        assert_eq!(prefixed(&verbose), format!("🐛 \n{verbose}"));
+    }
+
+    #[test]
+    fn other_comment_strings_are_respected() {
+        let message = CommitMessage::parse(";; Comment\nFix it\n".to_string(), ";;", None);
+        assert_eq!(message.subject(), "Fix it\n");
+        assert_eq!(message.with_prefix("🐛 ", 0), ";; Comment\n🐛 Fix it\n");
+    }
+
+    #[test]
+    fn messages_given_on_the_command_line_have_no_comments() {
+        let message = CommitMessage::parse("#123 Fix login\n".to_string(), "#", Some("message"));
+        assert_eq!(message.subject(), "#123 Fix login\n");
+        assert_eq!(message.with_prefix("🐛 ", 0), "🐛 #123 Fix login\n");
+        // Even when git adds some below for the editor.
+        let edited = "#123 Fix login\n\n# Please enter the commit message\n";
+        let message = CommitMessage::parse(edited.to_string(), "#", Some("message"));
+        assert_eq!(message.with_prefix("🐛 ", 0), format!("🐛 {edited}"));
+    }
+
+    #[test]
+    fn replacing_swaps_the_start_of_the_subject() {
+        let message = CommitMessage::parse("# Amending\n⚡️ Faster\n".to_string(), "#", None);
+        assert_eq!(message.subject(), "⚡️ Faster\n");
+        assert_eq!(
+            message.with_prefix("🐛", "⚡️".len()),
+            "# Amending\n🐛 Faster\n"
+        );
+    }
+
+    fn emojis(names: &[&str]) -> Vec<Emoji> {
+        names
+            .iter()
+            .map(|name| gimoji_core::lookup(name).unwrap().clone())
+            .collect()
+    }
+
+    /// `text` with the emojis `current` after its Conventional Commits
+    /// header, if any, replaced by `selected`.
+    fn replaced(text: &str, current: &[&str], selected: &[&str]) -> String {
+        let message = CommitMessage::parse(text.to_string(), "#", None);
+        let subject = message.subject();
+        let start = ConventionalHeader::parse(subject).map_or(0, |header| header.end());
+        let current = emojis(current);
+        let len = current
+            .iter()
+            .map(|emoji| {
+                let rest = &subject[start..];
+                [emoji.emoji(), emoji.code()]
+                    .into_iter()
+                    .find(|form| rest.contains(form))
+                    .unwrap()
+                    .len()
+            })
+            .sum();
+
+        message.with_replaced(&current, start, len, &emojis(selected))
+    }
+
+    #[test]
+    fn replacements_keep_the_form_emojis_were_written_in() {
+        assert_eq!(replaced("⚡️ Faster\n", &["zap"], &["bug"]), "🐛 Faster\n");
+        assert_eq!(
+            replaced(":zap: Faster\n", &["zap"], &["bug"]),
+            ":bug: Faster\n"
+        );
+    }
+
+    #[test]
+    fn replacements_swap_the_type_that_came_with_the_emoji() {
+        assert_eq!(
+            replaced("perf: ⚡️ Faster\n", &["zap"], &["bug"]),
+            "fix: 🐛 Faster\n"
+        );
+        assert_eq!(
+            replaced("perf(core)!: :zap: Faster\n", &["zap"], &["bug"]),
+            "fix(core)!: :bug: Faster\n"
+        );
+    }
+
+    #[test]
+    fn replacements_keep_a_type_the_emoji_did_not_bring() {
+        assert_eq!(
+            replaced("chore: ⚡️ Faster\n", &["zap"], &["bug"]),
+            "chore: 🐛 Faster\n"
+        );
+    }
+
+    #[test]
+    fn replacements_swap_several_emojis_at_once() {
+        assert_eq!(
+            replaced("# Amending\n📝🐛 Docs\n", &["memo", "bug"], &["sparkles"]),
+            "# Amending\n✨ Docs\n"
+        );
+        assert_eq!(
+            replaced("docs: :memo: Docs\n", &["memo"], &["zap", "bug"]),
+            "perf: :zap::bug: Docs\n"
+        );
+    }
+
+    #[test]
+    fn writes_replace_the_file() {
+        let path = std::env::temp_dir().join(format!("gimoji-msg-{}", std::process::id()));
+        fs::write(&path, "old").unwrap();
+        write_atomically(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::config;
+
+/// Emojis that likely go with a set of staged changes, e.g. 📝 when only
+/// docs changed.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// The emoji to suggest, by glyph, `:code:` or name.
+    pub emoji: String,
+    /// Globs every changed path has to match one of. `*` stops at `/` while
+    /// `**` doesn't, and globs without a `/` match file names anywhere.
+    pub paths: Vec<String>,
+    /// What every change has to be, if it matters.
+    #[serde(default)]
+    pub status: Option<Status>,
+}
+
+impl Rule {
+    fn new(emoji: &str, paths: &[&str], status: Option<Status>) -> Self {
+        Self {
+            emoji: emoji.to_string(),
+            paths: paths.iter().map(|path| path.to_string()).collect(),
+            status,
+        }
+    }
+
+    /// Whether all of `changes` are what the rule is about.
+    fn applies_to(&self, changes: &[Change]) -> bool {
+        !changes.is_empty()
+            && changes.iter().all(|change| {
+                self.status.is_none_or(|status| status == change.status)
+                    && self.paths.iter().any(|glob| matches(glob, &change.path))
+            })
+    }
+}
+
+/// How a file changed, as `git diff --name-status` tells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+    TypeChanged,
+}
+
+impl Status {
+    /// The status `git diff --name-status` writes as `letter`.
+    pub fn from_letter(letter: char) -> Option<Self> {
+        Some(match letter {
+            'A' => Self::Added,
+            'M' => Self::Modified,
+            'D' => Self::Deleted,
+            'R' => Self::Renamed,
+            'C' => Self::Copied,
+            'T' => Self::TypeChanged,
+            _ => return None,
+        })
+    }
+
+    fn letter(self) -> char {
+        match self {
+            Self::Added => 'A',
+            Self::Modified => 'M',
+            Self::Deleted => 'D',
+            Self::Renamed => 'R',
+            Self::Copied => 'C',
+            Self::TypeChanged => 'T',
+        }
+    }
+}
+
+/// A staged change to a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub status: Status,
+    /// Where the file is now, or was for deletions.
+    pub path: String,
+}
+
+impl fmt::Display for Change {
+    /// The way `git diff --name-status` shows it, e.g. `M src/main.rs`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.status.letter(), self.path)
+    }
+}
+
+/// The emojis the config's rules and, unless turned off, the built-in ones
+/// suggest for `changes`, in the order of the rules.
+pub fn suggestions(config: &config::Suggestions, changes: &[Change]) -> Vec<String> {
+    let builtin = if config.builtin.unwrap_or(true) {
+        builtin_rules()
+    } else {
+        Vec::new()
+    };
+    let mut emojis: Vec<String> = Vec::new();
+    for rule in config.rules.iter().flatten().chain(&builtin) {
+        if rule.applies_to(changes) && !emojis.contains(&rule.emoji) {
+            emojis.push(rule.emoji.clone());
+        }
+    }
+
+    emojis
+}
+
+fn builtin_rules() -> Vec<Rule> {
+    vec![
+        Rule::new("fire", &["**"], Some(Status::Deleted)),
+        Rule::new(
+            "memo",
+            &["*.md", "*.rst", "*.adoc", "docs/**", "doc/**", "LICENSE*"],
+            None,
+        ),
+        Rule::new("arrow_up", &["Cargo.toml", "Cargo.lock"], None),
+        Rule::new(
+            "white_check_mark",
+            &[
+                "tests/**",
+                "*_test.*",
+                "*_tests.*",
+                "test_*",
+                "*.test.*",
+                "*.spec.*",
+            ],
+            None,
+        ),
+        Rule::new(
+            "construction_worker",
+            &[".github/workflows/**", ".gitlab-ci.yml", ".circleci/**"],
+            None,
+        ),
+    ]
+}
+
+/// Whether `path` matches `glob`. Globs without a `/` match the file name,
+/// and `tests/**` matches `tests/` directories anywhere too, unless it's
+/// anchored at the root as `/tests/**`.
+fn matches(glob: &str, path: &str) -> bool {
+    if let Some(glob) = glob.strip_prefix('/') {
+        return matches_from(glob.as_bytes(), path.as_bytes());
+    }
+    if !glob.contains('/') {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        return matches_from(glob.as_bytes(), name.as_bytes());
+    }
+
+    let path = path.as_bytes();
+    matches_from(glob.as_bytes(), path)
+        || (0..path.len())
+            .filter(|&i| path[i] == b'/')
+            .any(|i| matches_from(glob.as_bytes(), &path[i + 1..]))
+}
+
+fn matches_from(glob: &[u8], path: &[u8]) -> bool {
+    match glob {
+        [] => path.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=path.len()).any(|i| matches_from(rest, &path[i..]))
+        }
+        [b'*', rest @ ..] => (0..=path.len())
+            .take_while(|&i| i == 0 || path[i - 1] != b'/')
+            .any(|i| matches_from(rest, &path[i..])),
+        [b'?', rest @ ..] => {
+            matches!(path, [c, path @ ..] if *c != b'/' && matches_from(rest, path))
+        }
+        [c, rest @ ..] => matches!(path, [p, path @ ..] if p == c && matches_from(rest, path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(changes: &[(char, &str)]) -> Vec<Change> {
+        changes
+            .iter()
+            .map(|&(letter, path)| Change {
+                status: Status::from_letter(letter).unwrap(),
+                path: path.to_string(),
+            })
+            .collect()
+    }
+
+    fn suggest(config: &str, staged: &[(char, &str)]) -> Vec<String> {
+        let config: config::Config = toml::from_str(config).unwrap();
+        suggestions(&config.suggestions, &changes(staged))
+    }
+
+    #[test]
+    fn globs_match_like_gitignore() {
+        assert!(matches("*.md", "README.md"));
+        assert!(matches("*.md", "docs/guide/intro.md"));
+        assert!(!matches("*.md", "src/main.rs"));
+        assert!(matches("docs/**", "docs/guide/intro.md"));
+        assert!(matches("tests/**", "crates/core/tests/app.rs"));
+        assert!(!matches("src/*.rs", "src/bin/main.rs"));
+        assert!(matches("src/**/*.rs", "src/main.rs"));
+        assert!(matches("src/**/*.rs", "src/bin/main.rs"));
+        assert!(matches("Cargo.?oml", "crates/core/Cargo.toml"));
+        assert!(matches("/docs/**", "docs/a.md"));
+        assert!(!matches("/docs/**", "crates/docs/a.md"));
+    }
+
+    #[test]
+    fn built_in_rules_cover_the_common_commits() {
+        assert_eq!(
+            suggest("", &[('M', "README.md"), ('A', "docs/a.md")]),
+            ["memo"]
+        );
+        assert_eq!(
+            suggest("", &[('M', "Cargo.toml"), ('M', "Cargo.lock")]),
+            ["arrow_up"]
+        );
+        assert_eq!(suggest("", &[('D', "old.rs"), ('D', "a.md")]), ["fire"]);
+        assert_eq!(suggest("", &[('M', "tests/app.rs")]), ["white_check_mark"]);
+        assert_eq!(
+            suggest("", &[('M', ".github/workflows/rust.yml")]),
+            ["construction_worker"]
+        );
+        assert!(suggest("", &[('M', "README.md"), ('M', "src/main.rs")]).is_empty());
+        assert!(suggest("", &[]).is_empty());
+    }
+
+    #[test]
+    fn configured_rules_come_first() {
+        let config = r#"
+            [[suggestions.rules]]
+            emoji = "globe_with_meridians"
+            paths = ["po/**", "*.ftl"]
+
+            [[suggestions.rules]]
+            emoji = "truck"
+            paths = ["**"]
+            status = "renamed"
+        "#;
+        assert_eq!(
+            suggest(config, &[('M', "po/de.po"), ('A', "i18n/en.ftl")]),
+            ["globe_with_meridians"]
+        );
+        assert_eq!(suggest(config, &[('R', "docs/old.md")]), ["truck", "memo"]);
+        let only = format!("{config}\n[suggestions]\nbuiltin = false");
+        assert_eq!(suggest(&only, &[('R', "docs/old.md")]), ["truck"]);
+    }
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::Repo;
+
+fn bump(repo: &Repo) -> String {
+    let output = repo.gimoji(&["bump", "--short"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+#[test]
+fn versions_count_from_the_last_reachable_tag() {
+    let repo = Repo::new();
+    repo.commit("🎉 Begin");
+    assert_eq!(bump(&repo), "0.0.0");
+    repo.git(&["tag", "v1.2.3"]);
+    repo.commit("🐛 Fix it");
+    assert_eq!(bump(&repo), "1.2.4");
+}
+
+#[test]
+fn tags_on_other_branches_count_as_none() {
+    let repo = Repo::new();
+    repo.commit("🎉 Begin");
+    repo.git(&["tag", "v1.2.3"]);
+    repo.git(&["checkout", "-q", "--orphan", "other"]);
+    repo.commit("✨ Start over");
+
+    // From 0.0.0, where features only bump the patch, rather than v1.2.3.
+    assert_eq!(bump(&repo), "0.0.1");
+}
@@ -0,0 +1,128 @@
+#![allow(dead_code)] // Each test crate uses its own share of the helpers.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A git repository of its own under the temp dir, and a home directory
+/// next to it, so neither the user's git config nor gimoji's get involved.
+/// Removed on drop.
+pub struct Repo {
+    root: PathBuf,
+}
+
+impl Repo {
+    pub fn new() -> Self {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let root = std::env::temp_dir().join(format!(
+            "gimoji-test-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("home")).unwrap();
+        fs::create_dir_all(root.join("repo")).unwrap();
+        let repo = Self { root };
+        repo.git(&["init", "-q", "-b", "main"]);
+
+        repo
+    }
+
+    /// The working tree.
+    pub fn path(&self) -> PathBuf {
+        self.root.join("repo")
+    }
+
+    /// `$HOME` for the commands run in the repository.
+    pub fn home(&self) -> PathBuf {
+        self.root.join("home")
+    }
+
+    /// Run git with `args` in the repository, panicking if it fails, and
+    /// return its output.
+    pub fn git(&self, args: &[&str]) -> String {
+        let output = self.command("git").args(args).output().unwrap();
+        assert!(
+            output.status.success(),
+            "git {args:?}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    /// Run gimoji with `args` in the repository.
+    pub fn gimoji(&self, args: &[&str]) -> Output {
+        self.command(env!("CARGO_BIN_EXE_gimoji"))
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    /// Commit whatever is staged, or nothing, with `message`, skipping the
+    /// hooks.
+    pub fn commit(&self, message: &str) {
+        self.git(&[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "--no-verify",
+            "-m",
+            message,
+        ]);
+    }
+
+    /// Write `contents` to `path` in the working tree, creating its
+    /// directory if needed.
+    pub fn write(&self, path: impl AsRef<Path>, contents: &str) -> PathBuf {
+        let path = self.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+
+        path
+    }
+
+    /// The contents of `path` in the working tree, if it's there.
+    pub fn read(&self, path: impl AsRef<Path>) -> Option<String> {
+        fs::read_to_string(self.path().join(path)).ok()
+    }
+
+    fn command(&self, program: &str) -> Command {
+        // Where installed hooks find gimoji.
+        let bin = Path::new(env!("CARGO_BIN_EXE_gimoji")).parent().unwrap();
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let path = std::env::join_paths(
+            std::iter::once(bin.to_path_buf()).chain(std::env::split_paths(&path)),
+        )
+        .unwrap();
+
+        let mut command = Command::new(program);
+        command
+            .env("PATH", path)
+            .current_dir(self.path())
+            .env("HOME", self.home())
+            .env("GIT_CONFIG_GLOBAL", self.home().join(".gitconfig"))
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("GIT_AUTHOR_NAME", "A U Thor")
+            .env("GIT_AUTHOR_EMAIL", "author@example.com")
+            .env("GIT_COMMITTER_NAME", "A U Thor")
+            .env("GIT_COMMITTER_EMAIL", "author@example.com")
+            .env_remove("GIT_DIR")
+            .env_remove("GIT_WORK_TREE")
+            .env_remove("GIT_INDEX_FILE")
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_STATE_HOME")
+            .stdin(Stdio::null());
+
+        command
+    }
+}
+
+impl Drop for Repo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
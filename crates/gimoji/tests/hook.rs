@@ -0,0 +1,196 @@
+mod common;
+
+use common::Repo;
+
+/// Run gimoji with `args` in `repo`, panicking if it fails, and return what
+/// it printed.
+fn run(repo: &Repo, args: &[&str]) -> String {
+    let output = repo.gimoji(args);
+    assert!(
+        output.status.success(),
+        "gimoji {args:?}: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// A repository with gimoji's `prepare-commit-msg` hook installed.
+fn hooked() -> Repo {
+    let repo = Repo::new();
+    run(&repo, &["--init"]);
+
+    repo
+}
+
+/// Commit with `message`, running the hooks, and return the message the
+/// commit ended up with.
+fn commit(repo: &Repo, message: &str) -> String {
+    repo.git(&["commit", "-q", "--allow-empty", "-m", message]);
+
+    last_message(repo)
+}
+
+/// The first line of the last commit's message.
+fn last_message(repo: &Repo) -> String {
+    let message = repo.git(&["log", "-1", "--format=%B"]);
+
+    message.lines().next().unwrap_or_default().to_string()
+}
+
+#[test]
+fn messages_get_the_emoji_their_words_hint_at() {
+    let repo = hooked();
+    assert_eq!(commit(&repo, "Fix crash on start"), "🐛 Fix crash on start");
+    // Not a comment: git keeps it, as no editor opens.
+    assert_eq!(commit(&repo, "#123 Fix login"), "🐛 #123 Fix login");
+    // An emoji that's there already stays.
+    assert_eq!(commit(&repo, "✨ Fix crash"), "✨ Fix crash");
+}
+
+#[test]
+fn messages_without_hints_get_the_default_emoji_or_none() {
+    let repo = hooked();
+    assert_eq!(commit(&repo, "Frobnicate"), "Frobnicate");
+
+    repo.write(".gimoji.toml", "[hook]\ndefault-emoji = \"sparkles\"\n");
+    assert_eq!(commit(&repo, "Frobnicate"), "✨ Frobnicate");
+}
+
+#[test]
+fn replayed_commits_keep_their_messages() {
+    let repo = Repo::new();
+    repo.commit("Begin");
+    repo.git(&["checkout", "-q", "-b", "topic"]);
+    repo.write("a", "a");
+    repo.git(&["add", "a"]);
+    repo.commit("Fix a");
+    repo.git(&["checkout", "-q", "main"]);
+    repo.write("b", "b");
+    repo.git(&["add", "b"]);
+    repo.commit("Add b");
+    run(&repo, &["--init"]);
+
+    repo.git(&["rebase", "-q", "main", "topic"]);
+    assert_eq!(last_message(&repo), "Fix a");
+
+    repo.git(&["checkout", "-q", "main"]);
+    repo.git(&["cherry-pick", "topic"]);
+    assert_eq!(last_message(&repo), "Fix a");
+}
+
+#[test]
+fn hooks_come_and_go_in_a_fresh_repository() {
+    let repo = Repo::new();
+    assert!(!repo.gimoji(&["--check"]).status.success());
+
+    run(&repo, &["--init"]);
+    assert!(repo
+        .read(HOOK)
+        .unwrap()
+        .starts_with("#!/usr/bin/env bash\n"));
+    assert!(run(&repo, &["--check"]).contains("prepare-commit-msg: installed"));
+
+    run(&repo, &["--uninstall"]);
+    assert_eq!(repo.read(HOOK), None);
+    assert!(!repo.gimoji(&["--check"]).status.success());
+}
+
+#[test]
+fn existing_scripts_are_given_back_as_they_were() {
+    let repo = Repo::new();
+    let script = "#!/bin/sh\n# Checks\nrun-checks \"$1\"  \n\n";
+    repo.write(HOOK, script);
+
+    run(&repo, &["--init"]);
+    let installed = repo.read(HOOK).unwrap();
+    assert!(installed.len() > script.len() && installed.starts_with(script));
+    assert!(run(&repo, &["--check"]).contains("prepare-commit-msg: installed"));
+
+    run(&repo, &["--uninstall"]);
+    assert_eq!(repo.read(HOOK).as_deref(), Some(script));
+}
+
+#[test]
+fn scripts_in_other_languages_are_left_to_the_user() {
+    let repo = Repo::new();
+    let script = "#!/usr/bin/env python3\nprint('checks')\n";
+    repo.write(HOOK, script);
+
+    let output = repo.gimoji(&["--init"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("gimoji --hook \"$1\" \"$2\""), "{stderr}");
+    assert_eq!(repo.read(HOOK).as_deref(), Some(script));
+}
+
+#[test]
+fn hooks_go_where_core_hooks_path_points() {
+    let repo = Repo::new();
+    repo.git(&["config", "core.hooksPath", "githooks"]);
+
+    run(&repo, &["--init"]);
+    assert!(repo.read("githooks/prepare-commit-msg").is_some());
+    assert_eq!(repo.read(HOOK), None);
+    assert!(run(&repo, &["--check"]).contains("githooks"));
+
+    run(&repo, &["--uninstall"]);
+    assert_eq!(repo.read("githooks/prepare-commit-msg"), None);
+}
+
+#[test]
+fn global_hooks_put_back_the_hooks_path_they_replaced() {
+    let repo = Repo::new();
+    let hooks_path = || repo.git(&["config", "--global", "core.hooksPath"]);
+    repo.git(&["config", "--global", "core.hooksPath", "~/hooks"]);
+
+    run(&repo, &["--init", "--global"]);
+    let dir = repo.home().join(".config/gimoji/hooks");
+    assert_eq!(hooks_path().trim_end(), dir.to_str().unwrap());
+    assert!(dir.join("pre-auto-gc").exists());
+    assert!(run(&repo, &["--check", "--global"]).contains("installed"));
+
+    run(&repo, &["--uninstall", "--global"]);
+    assert_eq!(hooks_path(), "~/hooks\n");
+    assert!(!dir.exists());
+    assert!(!repo.gimoji(&["--check", "--global"]).status.success());
+}
+
+#[test]
+fn global_hooks_run_gimoji_after_repository_hooks_without_it() {
+    let repo = Repo::new();
+    // Mentions gimoji, but doesn't run it.
+    repo.write(HOOK, "#!/bin/sh\n# Checks before gimoji\ntrue\n");
+    make_executable(&repo, HOOK);
+
+    run(&repo, &["--init", "--global"]);
+    assert_eq!(commit(&repo, "Fix crash"), "🐛 Fix crash");
+}
+
+#[test]
+fn global_hooks_leave_gimoji_to_repository_hooks_from_earlier_versions() {
+    let repo = Repo::new();
+    repo.write(
+        HOOK,
+        "#!/usr/bin/env bash\n# gimoji as a commit hook\ngimoji --hook \"$1\" \"$2\"",
+    );
+    make_executable(&repo, HOOK);
+    // A second run would offer to replace the emoji the first one applied,
+    // which fails without a terminal.
+    repo.write(".gimoji.toml", "[hook]\nreplace = true\n");
+
+    run(&repo, &["--init", "--global"]);
+    assert_eq!(commit(&repo, "Fix crash"), "🐛 Fix crash");
+}
+
+fn make_executable(repo: &Repo, path: &str) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = std::fs::Permissions::from_mode(0o755);
+        std::fs::set_permissions(repo.path().join(path), permissions).unwrap();
+    }
+}
+
+/// Where the hook goes without a `core.hooksPath`.
+const HOOK: &str = ".git/hooks/prepare-commit-msg";
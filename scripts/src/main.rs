@@ -36,6 +36,13 @@ pub struct Emoji {
     pub description: String,
     pub name: String,
     pub semver: Option<String>,
+    /// The Conventional Commits type, e.g. `fix`. Upstream gitmoji has
+    /// none, so ours are kept across updates.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub commit_type: Option<String>,
+    /// What the picker groups it under, e.g. `fixes`. Ours too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -100,8 +107,18 @@ fn merge_databases(current: EmojiDatabase, upstream: EmojiDatabase) -> (EmojiDat
         }
     }
 
-    // Start with upstream emojis (they take priority).
+    // Start with upstream emojis (they take priority), keeping the fields
+    // only our database has.
     let mut merged_emojis = upstream.gitmojis.clone();
+    for emoji in &mut merged_emojis {
+        let ours = current.gitmojis.iter().find(|e| e.code == emoji.code);
+        if emoji.commit_type.is_none() {
+            emoji.commit_type = ours.and_then(|e| e.commit_type.clone());
+        }
+        if emoji.category.is_none() {
+            emoji.category = ours.and_then(|e| e.category.clone());
+        }
+    }
 
     // Add emojis from current database that don't exist upstream.
     let mut custom_emojis = Vec::new();
@@ -254,6 +271,8 @@ mod tests {
                     description: "Old description".to_string(),
                     name: "art".to_string(),
                     semver: None,
+                    commit_type: Some("style".to_string()),
+                    category: Some("refactoring".to_string()),
                 },
                 Emoji {
                     emoji: "🤖".to_string(),
@@ -262,6 +281,8 @@ mod tests {
                     description: "Custom emoji".to_string(),
                     name: "robot".to_string(),
                     semver: None,
+                    commit_type: None,
+                    category: None,
                 },
             ],
         };
@@ -275,6 +296,8 @@ mod tests {
                 description: "New description".to_string(),
                 name: "art".to_string(),
                 semver: None,
+                commit_type: None,
+                category: None,
             }],
         };
 
@@ -291,6 +314,9 @@ mod tests {
         // Upstream should take priority.
         let art_emoji = merged.gitmojis.iter().find(|e| e.code == ":art:").unwrap();
         assert_eq!(art_emoji.description, "New description");
+        // Upstream has no types, so ours survive.
+        assert_eq!(art_emoji.commit_type.as_deref(), Some("style"));
+        assert_eq!(art_emoji.category.as_deref(), Some("refactoring"));
 
         // Custom emoji should be preserved.
         let robot_emoji = merged